@@ -0,0 +1,50 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use laurier::highlight::highlight_matched_text;
+use ratatui_core::{
+    style::{Color, Style},
+    text::Span,
+};
+
+fn rows(n: usize) -> Vec<String> {
+    (0..n)
+        .map(|i| format!("src/components/module_{i:05}/widget_item_name.rs"))
+        .collect()
+}
+
+fn bench_highlight_matched_text(c: &mut Criterion) {
+    let rows = rows(10_000);
+    let indices: Vec<usize> = vec![0, 1, 2, 8, 9, 10, 11, 30, 31, 32, 40, 41];
+
+    c.bench_function("highlight_matched_text 10k rows", |b| {
+        b.iter(|| {
+            for row in &rows {
+                let spans = highlight_matched_text(vec![Span::raw(row.as_str())])
+                    .matched_indices(indices.clone())
+                    .matched_fg(Color::Red)
+                    .into_spans();
+                black_box(spans);
+            }
+        })
+    });
+
+    c.bench_function("highlight_matched_text 10k rows styled spans", |b| {
+        b.iter(|| {
+            for row in &rows {
+                let (dir, file) = row.split_at(row.rfind('/').unwrap() + 1);
+                let spans = vec![
+                    Span::styled(dir, Style::default().fg(Color::DarkGray)),
+                    Span::raw(file),
+                ];
+                let spans = highlight_matched_text(spans)
+                    .matched_indices(indices.clone())
+                    .matched_fg(Color::Red)
+                    .ellipsis("…")
+                    .into_spans();
+                black_box(spans);
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_highlight_matched_text);
+criterion_main!(benches);
@@ -0,0 +1,351 @@
+use ratatui_core::{
+    layout::Alignment,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+use crate::spans::{align_spans, TruncatePosition};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColumnWidth {
+    Fixed(usize),
+    Flex(u16),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Column {
+    width: ColumnWidth,
+    min: usize,
+    max: usize,
+    alignment: Alignment,
+    truncate: TruncatePosition,
+}
+
+impl Column {
+    pub fn fixed(width: usize) -> Self {
+        Self::new(ColumnWidth::Fixed(width))
+    }
+
+    pub fn flex(weight: u16) -> Self {
+        Self::new(ColumnWidth::Flex(weight))
+    }
+
+    fn new(width: ColumnWidth) -> Self {
+        Self {
+            width,
+            min: 0,
+            max: usize::MAX,
+            alignment: Alignment::Left,
+            truncate: TruncatePosition::End,
+        }
+    }
+
+    pub fn min(mut self, min: usize) -> Self {
+        self.min = min;
+        self
+    }
+
+    pub fn max(mut self, max: usize) -> Self {
+        self.max = max;
+        self
+    }
+
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn truncate(mut self, truncate: TruncatePosition) -> Self {
+        self.truncate = truncate;
+        self
+    }
+}
+
+pub fn calc_column_widths(columns: &[Column], width: usize, separator_width: usize) -> Vec<usize> {
+    let mut visible: Vec<usize> = (0..columns.len()).collect();
+    loop {
+        let subset: Vec<Column> = visible.iter().map(|&i| columns[i]).collect();
+        let widths = distribute_widths(&subset, width, separator_width);
+        match widths.iter().rposition(|w| *w == 0) {
+            Some(i) if separator_width > 0 => {
+                visible.remove(i);
+            }
+            _ => {
+                let mut ret = vec![0; columns.len()];
+                for (i, w) in visible.into_iter().zip(widths) {
+                    ret[i] = w;
+                }
+                return ret;
+            }
+        }
+    }
+}
+
+fn distribute_widths(columns: &[Column], width: usize, separator_width: usize) -> Vec<usize> {
+    let separators_w = separator_width * columns.len().saturating_sub(1);
+    let available = width.saturating_sub(separators_w);
+
+    let mut widths: Vec<usize> = columns
+        .iter()
+        .map(|c| match c.width {
+            ColumnWidth::Fixed(w) => w,
+            ColumnWidth::Flex(_) => c.min.min(c.max),
+        })
+        .collect();
+
+    let mut rest = available.saturating_sub(widths.iter().sum());
+    while rest > 0 {
+        let growable: Vec<(usize, u16)> = columns
+            .iter()
+            .enumerate()
+            .filter_map(|(i, c)| match c.width {
+                ColumnWidth::Flex(weight) if weight > 0 && widths[i] < c.max => Some((i, weight)),
+                _ => None,
+            })
+            .collect();
+        if growable.is_empty() {
+            break;
+        }
+
+        let total_weight: usize = growable.iter().map(|(_, w)| *w as usize).sum();
+        let mut distributed = 0;
+        for (i, weight) in &growable {
+            let share = rest * *weight as usize / total_weight;
+            let add = share.min(columns[*i].max - widths[*i]);
+            widths[*i] += add;
+            distributed += add;
+        }
+        if distributed == 0 {
+            for (i, _) in growable.iter().take(rest) {
+                widths[*i] += 1;
+                distributed += 1;
+            }
+        }
+        rest -= distributed;
+    }
+
+    let mut over = widths.iter().sum::<usize>().saturating_sub(available);
+    for w in widths.iter_mut().rev() {
+        if over == 0 {
+            break;
+        }
+        let reduce = (*w).min(over);
+        *w -= reduce;
+        over -= reduce;
+    }
+
+    widths
+}
+
+pub fn format_columns<'a>(
+    columns: &[Column],
+    cells: Vec<Vec<Span<'a>>>,
+    width: usize,
+) -> FormatColumns<'a> {
+    FormatColumns {
+        columns: columns.to_vec(),
+        cells,
+        width,
+        separator: " ",
+        separator_style: Style::default(),
+        ellipsis: "…",
+        ellipsis_style: Style::default(),
+    }
+}
+
+pub struct FormatColumns<'a> {
+    columns: Vec<Column>,
+    cells: Vec<Vec<Span<'a>>>,
+    width: usize,
+    separator: &'a str,
+    separator_style: Style,
+    ellipsis: &'a str,
+    ellipsis_style: Style,
+}
+
+impl<'a> FormatColumns<'a> {
+    pub fn separator(mut self, separator: &'a str) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    pub fn separator_style(mut self, style: Style) -> Self {
+        self.separator_style = style;
+        self
+    }
+
+    pub fn ellipsis(mut self, ellipsis: &'a str) -> Self {
+        self.ellipsis = ellipsis;
+        self
+    }
+
+    pub fn ellipsis_style(mut self, style: Style) -> Self {
+        self.ellipsis_style = style;
+        self
+    }
+
+    pub fn ellipsis_fg(mut self, color: Color) -> Self {
+        self.ellipsis_style = self.ellipsis_style.fg(color);
+        self
+    }
+
+    pub fn ellipsis_bg(mut self, color: Color) -> Self {
+        self.ellipsis_style = self.ellipsis_style.bg(color);
+        self
+    }
+
+    pub fn ellipsis_modifier(mut self, modifier: Modifier) -> Self {
+        self.ellipsis_style = self.ellipsis_style.add_modifier(modifier);
+        self
+    }
+
+    pub fn into_line(self) -> Line<'a> {
+        Line::from(self.into_spans())
+    }
+
+    pub fn into_spans(self) -> Vec<Span<'a>> {
+        let separator_w = console::measure_text_width(self.separator);
+        let widths = calc_column_widths(&self.columns, self.width, separator_w);
+
+        let mut cells = self.cells.into_iter();
+        let mut ret = Vec::new();
+        let mut first = true;
+        for (column, w) in self.columns.iter().zip(widths) {
+            let cell = cells.next().unwrap_or_default();
+            if w == 0 {
+                continue;
+            }
+            if !first && !self.separator.is_empty() {
+                ret.push(Span::styled(self.separator, self.separator_style));
+            }
+            first = false;
+            let spans = align_spans(cell, w, column.alignment)
+                .ellipsis(self.ellipsis)
+                .ellipsis_style(self.ellipsis_style)
+                .truncate(column.truncate)
+                .into_spans();
+            ret.extend(spans);
+        }
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    fn line_to_string(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[rstest]
+    #[case(vec![Column::fixed(4), Column::flex(1)], 20, vec![4, 15])]
+    #[case(vec![Column::flex(1), Column::flex(1)], 21, vec![10, 10])]
+    #[case(vec![Column::flex(1), Column::flex(2)], 31, vec![10, 20])]
+    #[case(vec![Column::flex(1), Column::flex(1)], 22, vec![11, 10])]
+    #[case(vec![Column::flex(1).max(5), Column::flex(1)], 21, vec![5, 15])]
+    #[case(vec![Column::flex(1).max(5), Column::fixed(4)], 21, vec![5, 4])]
+    #[case(vec![Column::flex(1).min(8), Column::flex(1)], 13, vec![10, 2])]
+    #[case(vec![Column::fixed(10), Column::fixed(10)], 15, vec![10, 4])]
+    #[case(vec![Column::fixed(10), Column::flex(1).min(5)], 8, vec![8, 0])]
+    #[case(vec![Column::fixed(3), Column::fixed(0), Column::fixed(3)], 7, vec![3, 0, 3])]
+    #[case(vec![], 10, vec![])]
+    fn test_calc_column_widths(
+        #[case] columns: Vec<Column>,
+        #[case] width: usize,
+        #[case] expected: Vec<usize>,
+    ) {
+        assert_eq!(calc_column_widths(&columns, width, 1), expected);
+    }
+
+    #[test]
+    fn test_format_columns() {
+        let columns = [
+            Column::flex(1),
+            Column::fixed(6).alignment(Alignment::Right),
+            Column::fixed(5).alignment(Alignment::Center),
+        ];
+        let cells = vec![
+            vec![Span::raw("README"), Span::raw(".md")],
+            vec![Span::raw("1.2K")],
+            vec![Span::raw("Jan")],
+        ];
+        let actual = format_columns(&columns, cells, 24).into_line();
+        assert_eq!(line_to_string(&actual), "README.md     1.2K  Jan ");
+    }
+
+    #[rstest]
+    #[case(TruncatePosition::End, "very_lo…")]
+    #[case(TruncatePosition::Start, "…name.rs")]
+    #[case(TruncatePosition::Middle, "very….rs")]
+    fn test_format_columns_truncate(#[case] truncate: TruncatePosition, #[case] expected: &str) {
+        let columns = [Column::fixed(8).truncate(truncate), Column::fixed(0)];
+        let cells = vec![vec![Span::raw("very_long_name.rs")], vec![]];
+        let actual = format_columns(&columns, cells, 9).into_line();
+        assert_eq!(line_to_string(&actual), expected);
+    }
+
+    #[test]
+    fn test_format_columns_wide_chars() {
+        let columns = [
+            Column::fixed(5),
+            Column::fixed(4).alignment(Alignment::Right),
+        ];
+        let cells = vec![vec![Span::raw("日本語テキスト")], vec![Span::raw("漢字")]];
+        let actual = format_columns(&columns, cells, 10)
+            .separator("|")
+            .into_line();
+        assert_eq!(line_to_string(&actual), "日本…|漢字");
+        assert_eq!(actual.width(), 10);
+    }
+
+    #[test]
+    fn test_format_columns_styles() {
+        let columns = [Column::fixed(4), Column::fixed(3)];
+        let cells = vec![
+            vec![Span::styled("abcdef", Style::default().fg(Color::Red))],
+            vec![Span::raw("x")],
+        ];
+        let actual = format_columns(&columns, cells, 8)
+            .separator_style(Style::default().fg(Color::DarkGray))
+            .ellipsis_fg(Color::Blue)
+            .into_spans();
+        let expected = vec![
+            Span::styled("abc", Style::default().fg(Color::Red)),
+            Span::styled("…", Style::default().fg(Color::Blue)),
+            Span::styled(" ", Style::default().fg(Color::DarkGray)),
+            Span::raw("x"),
+            Span::raw("  "),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case(0, "")]
+    #[case(1, "a")]
+    #[case(3, "a  ")]
+    #[case(4, "a  ")]
+    #[case(7, "a   b  ")]
+    #[case(11, "a   b   c  ")]
+    fn test_format_columns_narrow(#[case] width: usize, #[case] expected: &str) {
+        let columns = [Column::fixed(3), Column::fixed(3), Column::fixed(3)];
+        let cells = vec![
+            vec![Span::raw("a")],
+            vec![Span::raw("b")],
+            vec![Span::raw("c")],
+        ];
+        let actual = format_columns(&columns, cells, width).into_line();
+        assert_eq!(line_to_string(&actual), expected);
+        assert!(actual.width() <= width);
+    }
+
+    #[test]
+    fn test_format_columns_missing_cells() {
+        let columns = [Column::fixed(3), Column::fixed(3)];
+        let cells = vec![vec![Span::raw("a")]];
+        let actual = format_columns(&columns, cells, 7).into_line();
+        assert_eq!(line_to_string(&actual), "a      ");
+    }
+}
@@ -6,6 +6,8 @@ use ratatui_core::{
 };
 use ratatui_widgets::{block::Block, clear::Clear};
 
+use crate::layout::RectExt;
+
 type DialogContent<'a> = Box<dyn FnOnce(Rect, &mut Buffer) + 'a>;
 
 pub struct Dialog<'a> {
@@ -42,20 +44,9 @@ impl Widget for Dialog<'_> {
 
 impl Dialog<'_> {
     fn render_dialog(self, area: Rect, buf: &mut Buffer) {
-        let outer = outer_rect(area, self.margin);
+        let outer = area.outset(self.margin);
         Clear.render(outer, buf);
         Block::default().bg(self.bg).render(outer, buf);
         (self.content)(area, buf);
     }
 }
-
-fn outer_rect(r: Rect, margin: Margin) -> Rect {
-    let doubled_margin_horizontal = margin.horizontal.saturating_mul(2);
-    let doubled_margin_vertical = margin.vertical.saturating_mul(2);
-    Rect {
-        x: r.x.saturating_sub(margin.horizontal),
-        y: r.y.saturating_sub(margin.vertical),
-        width: r.width.saturating_add(doubled_margin_horizontal),
-        height: r.height.saturating_add(doubled_margin_vertical),
-    }
-}
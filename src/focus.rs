@@ -0,0 +1,424 @@
+use ratatui_core::{layout::Rect, style::Style};
+use ratatui_crossterm::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{key_code, keys::is_press_or_repeat};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FocusDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FocusRing<K> {
+    layers: Vec<FocusLayer<K>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FocusLayer<K> {
+    items: Vec<(K, Rect)>,
+    focused: usize,
+}
+
+impl<K> FocusLayer<K> {
+    fn new(ids: impl IntoIterator<Item = K>) -> Self {
+        Self {
+            items: ids.into_iter().map(|id| (id, Rect::ZERO)).collect(),
+            focused: 0,
+        }
+    }
+}
+
+impl<K: PartialEq> FocusRing<K> {
+    pub fn new(ids: impl IntoIterator<Item = K>) -> Self {
+        Self {
+            layers: vec![FocusLayer::new(ids)],
+        }
+    }
+
+    pub fn focused(&self) -> Option<&K> {
+        let layer = self.layer();
+        layer.items.get(layer.focused).map(|(id, _)| id)
+    }
+
+    pub fn is_focused(&self, id: &K) -> bool {
+        self.focused() == Some(id)
+    }
+
+    pub fn focus_style(&self, id: &K, focused: Style, unfocused: Style) -> Style {
+        if self.is_focused(id) {
+            focused
+        } else {
+            unfocused
+        }
+    }
+
+    pub fn focus(&mut self, id: &K) -> bool {
+        let layer = self.layer_mut();
+        match layer.items.iter().position(|(k, _)| k == id) {
+            Some(i) => {
+                layer.focused = i;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn focus_next(&mut self) {
+        let layer = self.layer_mut();
+        if !layer.items.is_empty() {
+            layer.focused = (layer.focused + 1) % layer.items.len();
+        }
+    }
+
+    pub fn focus_prev(&mut self) {
+        let layer = self.layer_mut();
+        if !layer.items.is_empty() {
+            let n = layer.items.len();
+            layer.focused = (layer.focused + n - 1) % n;
+        }
+    }
+
+    pub fn focus_direction(&mut self, direction: FocusDirection) -> bool {
+        let layer = self.layer_mut();
+        let Some((_, current)) = layer.items.get(layer.focused) else {
+            return false;
+        };
+        let current = *current;
+        if current.is_empty() {
+            return false;
+        }
+
+        let target = layer
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(i, (_, r))| *i != layer.focused && !r.is_empty())
+            .filter_map(|(i, (_, r))| direction_score(current, *r, direction).map(|s| (s, i)))
+            .min();
+
+        match target {
+            Some((_, i)) => {
+                layer.focused = i;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn set_area(&mut self, id: &K, area: Rect) {
+        for layer in &mut self.layers {
+            for (k, r) in &mut layer.items {
+                if k == id {
+                    *r = area;
+                }
+            }
+        }
+    }
+
+    pub fn push_layer(&mut self, ids: impl IntoIterator<Item = K>) {
+        self.layers.push(FocusLayer::new(ids));
+    }
+
+    pub fn pop_layer(&mut self) -> bool {
+        if self.layers.len() > 1 {
+            self.layers.pop();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn has_layer(&self) -> bool {
+        self.layers.len() > 1
+    }
+
+    pub fn handle_key_event(&mut self, e: &KeyEvent) -> bool {
+        if !is_press_or_repeat(e) {
+            return false;
+        }
+        let ctrl = e.modifiers.contains(KeyModifiers::CONTROL);
+        let none = e.modifiers.is_empty();
+        let shift = e.modifiers == KeyModifiers::SHIFT;
+        match e {
+            key_code!(KeyCode::Tab) if none => {
+                self.focus_next();
+                true
+            }
+            key_code!(KeyCode::Tab) if shift => {
+                self.focus_prev();
+                true
+            }
+            key_code!(KeyCode::BackTab) if none || shift => {
+                self.focus_prev();
+                true
+            }
+            key_code!(KeyCode::Esc) if none => self.pop_layer(),
+            key_code!(KeyCode::Left) if ctrl => self.focus_direction(FocusDirection::Left),
+            key_code!(KeyCode::Right) if ctrl => self.focus_direction(FocusDirection::Right),
+            key_code!(KeyCode::Up) if ctrl => self.focus_direction(FocusDirection::Up),
+            key_code!(KeyCode::Down) if ctrl => self.focus_direction(FocusDirection::Down),
+            _ => false,
+        }
+    }
+
+    fn layer(&self) -> &FocusLayer<K> {
+        self.layers.last().unwrap()
+    }
+
+    fn layer_mut(&mut self) -> &mut FocusLayer<K> {
+        self.layers.last_mut().unwrap()
+    }
+}
+
+fn direction_score(from: Rect, to: Rect, direction: FocusDirection) -> Option<(u32, u32)> {
+    let (distance, from_range, to_range) = match direction {
+        FocusDirection::Left if to.right() <= from.left() => (
+            from.left() - to.right(),
+            (from.top(), from.bottom()),
+            (to.top(), to.bottom()),
+        ),
+        FocusDirection::Right if to.left() >= from.right() => (
+            to.left() - from.right(),
+            (from.top(), from.bottom()),
+            (to.top(), to.bottom()),
+        ),
+        FocusDirection::Up if to.bottom() <= from.top() => (
+            from.top() - to.bottom(),
+            (from.left(), from.right()),
+            (to.left(), to.right()),
+        ),
+        FocusDirection::Down if to.top() >= from.bottom() => (
+            to.top() - from.bottom(),
+            (from.left(), from.right()),
+            (to.left(), to.right()),
+        ),
+        _ => return None,
+    };
+    let misalignment = range_gap(from_range, to_range);
+    let from_center = from_range.0 as u32 + from_range.1 as u32;
+    let to_center = to_range.0 as u32 + to_range.1 as u32;
+    Some((
+        distance as u32 + misalignment as u32 * 2,
+        from_center.abs_diff(to_center),
+    ))
+}
+
+fn range_gap(a: (u16, u16), b: (u16, u16)) -> u16 {
+    b.0.saturating_sub(a.1).max(a.0.saturating_sub(b.1))
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui_core::{
+        buffer::Buffer,
+        style::{Color, Modifier},
+        text::{Line, Span},
+        widgets::Widget,
+    };
+    use ratatui_crossterm::crossterm::event::KeyEventKind;
+    use rstest::*;
+
+    use super::*;
+    use crate::dialog::Dialog;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Pane {
+        Tree,
+        List,
+        Preview,
+        Status,
+        Ok,
+        Cancel,
+    }
+
+    // +------+-----------+
+    // | Tree | List      |
+    // |      +-----------+
+    // |      | Preview   |
+    // +------+-----------+
+    // | Status           |
+    // +------------------+
+    fn focus_ring() -> FocusRing<Pane> {
+        let mut ring = FocusRing::new([Pane::Tree, Pane::List, Pane::Preview, Pane::Status]);
+        ring.set_area(&Pane::Tree, Rect::new(0, 0, 10, 20));
+        ring.set_area(&Pane::List, Rect::new(10, 0, 30, 10));
+        ring.set_area(&Pane::Preview, Rect::new(10, 10, 30, 10));
+        ring.set_area(&Pane::Status, Rect::new(0, 20, 40, 1));
+        ring
+    }
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_focus_next_prev() {
+        let mut ring = focus_ring();
+        assert_eq!(ring.focused(), Some(&Pane::Tree));
+        ring.focus_next();
+        assert_eq!(ring.focused(), Some(&Pane::List));
+        ring.focus_next();
+        ring.focus_next();
+        assert_eq!(ring.focused(), Some(&Pane::Status));
+        ring.focus_next();
+        assert_eq!(ring.focused(), Some(&Pane::Tree));
+        ring.focus_prev();
+        assert_eq!(ring.focused(), Some(&Pane::Status));
+    }
+
+    #[test]
+    fn test_focus() {
+        let mut ring = focus_ring();
+        assert!(ring.focus(&Pane::Preview));
+        assert!(ring.is_focused(&Pane::Preview));
+        assert!(!ring.is_focused(&Pane::Tree));
+        assert!(!ring.focus(&Pane::Ok));
+        assert!(ring.is_focused(&Pane::Preview));
+    }
+
+    #[rstest]
+    #[case(Pane::Tree, FocusDirection::Right, Some(Pane::List))]
+    #[case(Pane::Tree, FocusDirection::Down, Some(Pane::Status))]
+    #[case(Pane::Tree, FocusDirection::Left, None)]
+    #[case(Pane::Tree, FocusDirection::Up, None)]
+    #[case(Pane::List, FocusDirection::Left, Some(Pane::Tree))]
+    #[case(Pane::List, FocusDirection::Down, Some(Pane::Preview))]
+    #[case(Pane::Preview, FocusDirection::Up, Some(Pane::List))]
+    #[case(Pane::Preview, FocusDirection::Down, Some(Pane::Status))]
+    #[case(Pane::Preview, FocusDirection::Left, Some(Pane::Tree))]
+    #[case(Pane::Status, FocusDirection::Up, Some(Pane::Preview))]
+    fn test_focus_direction(
+        #[case] from: Pane,
+        #[case] direction: FocusDirection,
+        #[case] expected: Option<Pane>,
+    ) {
+        let mut ring = focus_ring();
+        ring.focus(&from);
+        assert_eq!(ring.focus_direction(direction), expected.is_some());
+        assert_eq!(ring.focused(), Some(&expected.unwrap_or(from)));
+    }
+
+    #[test]
+    fn test_focus_direction_skips_hidden() {
+        let mut ring = focus_ring();
+        ring.set_area(&Pane::List, Rect::ZERO);
+        assert!(ring.focus_direction(FocusDirection::Right));
+        assert_eq!(ring.focused(), Some(&Pane::Preview));
+    }
+
+    #[test]
+    fn test_handle_key_event() {
+        let mut ring = focus_ring();
+        assert!(ring.handle_key_event(&key(KeyCode::Tab, KeyModifiers::NONE)));
+        assert_eq!(ring.focused(), Some(&Pane::List));
+        assert!(ring.handle_key_event(&key(KeyCode::BackTab, KeyModifiers::SHIFT)));
+        assert_eq!(ring.focused(), Some(&Pane::Tree));
+        assert!(ring.handle_key_event(&key(KeyCode::Right, KeyModifiers::CONTROL)));
+        assert_eq!(ring.focused(), Some(&Pane::List));
+        assert!(!ring.handle_key_event(&key(KeyCode::Right, KeyModifiers::CONTROL)));
+        assert!(!ring.handle_key_event(&key(KeyCode::Left, KeyModifiers::NONE)));
+        assert_eq!(ring.focused(), Some(&Pane::List));
+
+        let release =
+            KeyEvent::new_with_kind(KeyCode::Tab, KeyModifiers::NONE, KeyEventKind::Release);
+        assert!(!ring.handle_key_event(&release));
+        assert_eq!(ring.focused(), Some(&Pane::List));
+
+        assert!(ring.handle_key_event(&key(KeyCode::Tab, KeyModifiers::SHIFT)));
+        assert_eq!(ring.focused(), Some(&Pane::Tree));
+        assert!(ring.handle_key_event(&key(KeyCode::BackTab, KeyModifiers::NONE)));
+        assert_eq!(ring.focused(), Some(&Pane::Status));
+        assert!(!ring.handle_key_event(&key(KeyCode::Esc, KeyModifiers::NONE)));
+    }
+
+    #[rstest]
+    #[case(KeyCode::Tab, KeyModifiers::CONTROL)]
+    #[case(KeyCode::Tab, KeyModifiers::ALT)]
+    #[case(KeyCode::BackTab, KeyModifiers::CONTROL | KeyModifiers::SHIFT)]
+    #[case(KeyCode::BackTab, KeyModifiers::ALT | KeyModifiers::SHIFT)]
+    fn test_handle_key_event_ignores_modified_tab(
+        #[case] code: KeyCode,
+        #[case] modifiers: KeyModifiers,
+    ) {
+        let mut ring = focus_ring();
+        assert!(!ring.handle_key_event(&key(code, modifiers)));
+        assert_eq!(ring.focused(), Some(&Pane::Tree));
+    }
+
+    #[test]
+    fn test_focus_layer_dialog() {
+        let mut ring = focus_ring();
+        ring.focus(&Pane::Preview);
+
+        ring.push_layer([Pane::Ok, Pane::Cancel]);
+        let area = Rect::new(0, 0, 40, 21);
+        let dialog_area = Rect::new(10, 10, 20, 1);
+        let ok = Rect::new(10, 10, 2, 1);
+        let cancel = Rect::new(13, 10, 6, 1);
+        ring.set_area(&Pane::Ok, ok);
+        ring.set_area(&Pane::Cancel, cancel);
+
+        let mut buf = Buffer::empty(area);
+        Dialog::new(Line::from(vec![
+            Span::styled(
+                "OK",
+                ring.focus_style(&Pane::Ok, Style::new().reversed(), Style::new()),
+            ),
+            Span::raw(" Cancel"),
+        ]))
+        .render(dialog_area, &mut buf);
+        assert_eq!(buf[(ok.x, ok.y)].style().add_modifier, Modifier::REVERSED);
+
+        assert!(ring.handle_key_event(&key(KeyCode::Right, KeyModifiers::CONTROL)));
+        assert_eq!(ring.focused(), Some(&Pane::Cancel));
+        assert!(ring.handle_key_event(&key(KeyCode::Tab, KeyModifiers::NONE)));
+        assert_eq!(ring.focused(), Some(&Pane::Ok));
+
+        assert!(ring.handle_key_event(&key(KeyCode::Esc, KeyModifiers::NONE)));
+        assert!(!ring.has_layer());
+        assert_eq!(ring.focused(), Some(&Pane::Preview));
+    }
+
+    #[test]
+    fn test_focus_layer() {
+        let mut ring = focus_ring();
+        ring.focus(&Pane::Preview);
+
+        ring.push_layer([Pane::Ok, Pane::Cancel]);
+        assert!(ring.has_layer());
+        assert_eq!(ring.focused(), Some(&Pane::Ok));
+        assert!(!ring.is_focused(&Pane::Preview));
+        assert!(!ring.focus(&Pane::Tree));
+
+        ring.focus_next();
+        assert_eq!(ring.focused(), Some(&Pane::Cancel));
+        ring.focus_next();
+        assert_eq!(ring.focused(), Some(&Pane::Ok));
+
+        assert!(ring.pop_layer());
+        assert!(!ring.has_layer());
+        assert_eq!(ring.focused(), Some(&Pane::Preview));
+        assert!(!ring.pop_layer());
+    }
+
+    #[test]
+    fn test_focus_style() {
+        let ring = focus_ring();
+        let focused = Style::default().fg(Color::Yellow);
+        let unfocused = Style::default().fg(Color::DarkGray);
+        assert_eq!(ring.focus_style(&Pane::Tree, focused, unfocused), focused);
+        assert_eq!(ring.focus_style(&Pane::List, focused, unfocused), unfocused);
+    }
+
+    #[test]
+    fn test_empty_focus_ring() {
+        let mut ring: FocusRing<Pane> = FocusRing::new([]);
+        ring.focus_next();
+        ring.focus_prev();
+        assert_eq!(ring.focused(), None);
+        assert!(!ring.focus_direction(FocusDirection::Left));
+    }
+}
@@ -0,0 +1,580 @@
+use std::time::{Duration, SystemTime};
+
+use ratatui_core::{
+    style::{Color, Modifier, Style},
+    text::Span,
+};
+
+use crate::spans::take_head;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Number(String),
+    Unit(&'static str),
+    Text(&'static str),
+}
+
+type Form = Vec<Part>;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FormatStyle {
+    number_style: Style,
+    unit_style: Style,
+    max_width: Option<usize>,
+}
+
+pub trait FormatStyled: Sized {
+    fn format_style_mut(&mut self) -> &mut FormatStyle;
+
+    fn number_style(mut self, style: Style) -> Self {
+        self.format_style_mut().number_style = style;
+        self
+    }
+
+    fn number_fg(mut self, color: Color) -> Self {
+        let style = self.format_style_mut();
+        style.number_style = style.number_style.fg(color);
+        self
+    }
+
+    fn number_bg(mut self, color: Color) -> Self {
+        let style = self.format_style_mut();
+        style.number_style = style.number_style.bg(color);
+        self
+    }
+
+    fn number_modifier(mut self, modifier: Modifier) -> Self {
+        let style = self.format_style_mut();
+        style.number_style = style.number_style.add_modifier(modifier);
+        self
+    }
+
+    fn unit_style(mut self, style: Style) -> Self {
+        self.format_style_mut().unit_style = style;
+        self
+    }
+
+    fn unit_fg(mut self, color: Color) -> Self {
+        let style = self.format_style_mut();
+        style.unit_style = style.unit_style.fg(color);
+        self
+    }
+
+    fn unit_bg(mut self, color: Color) -> Self {
+        let style = self.format_style_mut();
+        style.unit_style = style.unit_style.bg(color);
+        self
+    }
+
+    fn unit_modifier(mut self, modifier: Modifier) -> Self {
+        let style = self.format_style_mut();
+        style.unit_style = style.unit_style.add_modifier(modifier);
+        self
+    }
+
+    fn max_width(mut self, max_width: usize) -> Self {
+        self.format_style_mut().max_width = Some(max_width);
+        self
+    }
+}
+
+impl FormatStyle {
+    fn into_spans(self, forms: Vec<Form>) -> Vec<Span<'static>> {
+        let width = |form: &Form| -> usize {
+            form.iter()
+                .map(|p| match p {
+                    Part::Number(s) => console::measure_text_width(s),
+                    Part::Unit(s) | Part::Text(s) => console::measure_text_width(s),
+                })
+                .sum()
+        };
+        let form = match self.max_width {
+            Some(max) => forms
+                .iter()
+                .find(|f| width(f) <= max)
+                .or_else(|| forms.iter().min_by_key(|f| width(f)))
+                .cloned()
+                .unwrap_or_default(),
+            None => forms.into_iter().next().unwrap_or_default(),
+        };
+        let spans = form
+            .into_iter()
+            .map(|p| match p {
+                Part::Number(s) => Span::styled(s, self.number_style),
+                Part::Unit(s) => Span::styled(s, self.unit_style),
+                Part::Text(s) => Span::raw(s),
+            })
+            .collect();
+        match self.max_width {
+            Some(max) => take_head(spans, max),
+            None => spans,
+        }
+    }
+}
+
+const BINARY_UNITS: [(&str, &str); 7] = [
+    ("B", "B"),
+    ("KiB", "K"),
+    ("MiB", "M"),
+    ("GiB", "G"),
+    ("TiB", "T"),
+    ("PiB", "P"),
+    ("EiB", "E"),
+];
+
+const SI_UNITS: [(&str, &str); 7] = [
+    ("B", "B"),
+    ("kB", "k"),
+    ("MB", "M"),
+    ("GB", "G"),
+    ("TB", "T"),
+    ("PB", "P"),
+    ("EB", "E"),
+];
+
+pub fn format_bytes(bytes: u64) -> FormatBytes {
+    FormatBytes {
+        bytes,
+        si: false,
+        style: FormatStyle::default(),
+    }
+}
+
+pub struct FormatBytes {
+    bytes: u64,
+    si: bool,
+    style: FormatStyle,
+}
+
+impl FormatStyled for FormatBytes {
+    fn format_style_mut(&mut self) -> &mut FormatStyle {
+        &mut self.style
+    }
+}
+
+impl FormatBytes {
+    pub fn si(mut self, si: bool) -> Self {
+        self.si = si;
+        self
+    }
+
+    pub fn into_spans(self) -> Vec<Span<'static>> {
+        let (base, units) = if self.si {
+            (1000.0, SI_UNITS)
+        } else {
+            (1024.0, BINARY_UNITS)
+        };
+
+        let (value, i) = scale(self.bytes as f64, base, units.len(), |v, i| {
+            round_to(v, bytes_decimals(v, i))
+        });
+        let (unit, short) = units[i];
+
+        let precise = format_decimals(value, bytes_decimals(value, i));
+        let forms = vec![
+            vec![
+                Part::Number(precise.clone()),
+                Part::Text(" "),
+                Part::Unit(unit),
+            ],
+            vec![Part::Number(precise), Part::Unit(short)],
+            vec![Part::Number(format_decimals(value, 0)), Part::Unit(short)],
+        ];
+        self.style.into_spans(forms)
+    }
+}
+
+fn bytes_decimals(value: f64, unit: usize) -> usize {
+    if unit == 0 || round_to(value, 1) >= 100.0 {
+        0
+    } else {
+        1
+    }
+}
+
+fn scale(
+    mut value: f64,
+    base: f64,
+    units: usize,
+    rounded: impl Fn(f64, usize) -> f64,
+) -> (f64, usize) {
+    let mut i = 0;
+    while rounded(value, i) >= base && i < units - 1 {
+        value /= base;
+        i += 1;
+    }
+    (value, i)
+}
+
+fn round_to(value: f64, decimals: usize) -> f64 {
+    let factor = 10f64.powi(decimals as i32);
+    (value * factor).round() / factor
+}
+
+fn format_decimals(value: f64, decimals: usize) -> String {
+    format!("{:.*}", decimals, round_to(value, decimals))
+}
+
+const DURATION_UNITS: [(u64, &str); 4] = [(86400, "d"), (3600, "h"), (60, "m"), (1, "s")];
+
+pub fn format_duration(duration: Duration) -> FormatDuration {
+    FormatDuration {
+        duration,
+        style: FormatStyle::default(),
+    }
+}
+
+pub struct FormatDuration {
+    duration: Duration,
+    style: FormatStyle,
+}
+
+impl FormatStyled for FormatDuration {
+    fn format_style_mut(&mut self) -> &mut FormatStyle {
+        &mut self.style
+    }
+}
+
+impl FormatDuration {
+    pub fn into_spans(self) -> Vec<Span<'static>> {
+        let secs = self.duration.as_secs();
+        if secs == 0 {
+            let ms = self.duration.as_millis().to_string();
+            let forms = vec![vec![Part::Number(ms), Part::Unit("ms")]];
+            return self.style.into_spans(forms);
+        }
+
+        let mut rest = secs;
+        let components: Vec<(u64, &'static str)> = DURATION_UNITS
+            .iter()
+            .filter_map(|(unit_secs, unit)| {
+                let n = rest / unit_secs;
+                rest %= unit_secs;
+                (n > 0).then_some((n, *unit))
+            })
+            .collect();
+
+        let forms = (1..=components.len())
+            .rev()
+            .map(|n| {
+                let mut form = Vec::new();
+                for (i, (value, unit)) in components[..n].iter().enumerate() {
+                    if i > 0 {
+                        form.push(Part::Text(" "));
+                    }
+                    form.push(Part::Number(value.to_string()));
+                    form.push(Part::Unit(unit));
+                }
+                form
+            })
+            .collect();
+        self.style.into_spans(forms)
+    }
+}
+
+type RelativeUnit = (u64, &'static str, [&'static str; 2], [&'static str; 2]);
+
+const RELATIVE_UNITS: [RelativeUnit; 6] = [
+    (365 * 86400, "y", ["yr", "yrs"], ["year", "years"]),
+    (30 * 86400, "mo", ["mo", "mos"], ["month", "months"]),
+    (7 * 86400, "w", ["wk", "wks"], ["week", "weeks"]),
+    (86400, "d", ["day", "days"], ["day", "days"]),
+    (3600, "h", ["hr", "hrs"], ["hour", "hours"]),
+    (60, "m", ["min", "min"], ["minute", "minutes"]),
+];
+
+pub fn format_relative_time(time: SystemTime) -> FormatRelativeTime {
+    FormatRelativeTime {
+        time,
+        now: None,
+        style: FormatStyle::default(),
+    }
+}
+
+pub struct FormatRelativeTime {
+    time: SystemTime,
+    now: Option<SystemTime>,
+    style: FormatStyle,
+}
+
+impl FormatStyled for FormatRelativeTime {
+    fn format_style_mut(&mut self) -> &mut FormatStyle {
+        &mut self.style
+    }
+}
+
+impl FormatRelativeTime {
+    pub fn now(mut self, now: SystemTime) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    pub fn into_spans(self) -> Vec<Span<'static>> {
+        let now = self.now.unwrap_or_else(SystemTime::now);
+        let (secs, future) = match now.duration_since(self.time) {
+            Ok(d) => (d.as_secs(), false),
+            Err(e) => (e.duration().as_secs(), true),
+        };
+
+        if secs < 60 {
+            let forms = vec![vec![Part::Text("just now")], vec![Part::Text("now")]];
+            return self.style.into_spans(forms);
+        }
+
+        let (unit_secs, short, medium, long) = RELATIVE_UNITS
+            .iter()
+            .find(|(unit_secs, ..)| secs >= *unit_secs)
+            .copied()
+            .unwrap();
+        let n = secs / unit_secs;
+        let number = || Part::Number(n.to_string());
+        let plural = usize::from(n != 1);
+
+        let forms = [long[plural], medium[plural], short]
+            .into_iter()
+            .map(|unit| {
+                let space = if unit == short { "" } else { " " };
+                if future {
+                    vec![
+                        Part::Text("in "),
+                        number(),
+                        Part::Text(space),
+                        Part::Unit(unit),
+                    ]
+                } else {
+                    vec![
+                        number(),
+                        Part::Text(space),
+                        Part::Unit(unit),
+                        Part::Text(" ago"),
+                    ]
+                }
+            })
+            .chain([vec![
+                Part::Text(if future { "+" } else { "-" }),
+                number(),
+                Part::Unit(short),
+            ]])
+            .map(|form| form.into_iter().filter(|p| *p != Part::Text("")).collect())
+            .collect();
+        self.style.into_spans(forms)
+    }
+}
+
+pub fn format_count(count: u64) -> FormatCount {
+    FormatCount {
+        count,
+        style: FormatStyle::default(),
+    }
+}
+
+pub struct FormatCount {
+    count: u64,
+    style: FormatStyle,
+}
+
+impl FormatStyled for FormatCount {
+    fn format_style_mut(&mut self) -> &mut FormatStyle {
+        &mut self.style
+    }
+}
+
+impl FormatCount {
+    pub fn into_spans(self) -> Vec<Span<'static>> {
+        let digits = self.count.to_string();
+        let mut grouped = String::new();
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i) % 3 == 0 {
+                grouped.push(',');
+            }
+            grouped.push(c);
+        }
+
+        let mut forms = vec![vec![Part::Number(grouped)]];
+        let units = ["", "k", "M", "G", "T", "P", "E"];
+        let (value, i) = scale(self.count as f64, 1000.0, units.len(), |v, _| {
+            round_to(v, 0)
+        });
+        if i > 0 {
+            if round_to(value, 1) < 100.0 {
+                let number = format_decimals(value, 1);
+                forms.push(vec![Part::Number(number), Part::Unit(units[i])]);
+            }
+            let number = format_decimals(value, 0);
+            forms.push(vec![Part::Number(number), Part::Unit(units[i])]);
+        }
+        self.style.into_spans(forms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui_core::style::Stylize;
+    use rstest::*;
+
+    use super::*;
+    use crate::spans::spans_width;
+
+    fn to_string(spans: &[Span]) -> String {
+        spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[rstest]
+    #[case(0, false, None, "0 B")]
+    #[case(512, false, None, "512 B")]
+    #[case(1024, false, None, "1.0 KiB")]
+    #[case(1536, false, None, "1.5 KiB")]
+    #[case(150 * 1024 * 1024, false, None, "150 MiB")]
+    #[case(1536, true, None, "1.5 kB")]
+    #[case(u64::MAX, false, None, "16.0 EiB")]
+    #[case(1536, false, Some(6), "1.5K")]
+    #[case(1536, false, Some(3), "2K")]
+    #[case(1536, false, Some(1), "2")]
+    #[case(1536, false, Some(0), "")]
+    #[case(512, false, Some(4), "512B")]
+    #[case(1048575, false, None, "1.0 MiB")]
+    #[case(1048575, false, Some(2), "1M")]
+    #[case(1023 * 1024 + 900, false, None, "1.0 MiB")]
+    #[case(999_999, true, None, "1.0 MB")]
+    #[case(99_960, true, None, "100 kB")]
+    #[case(99_940, true, None, "99.9 kB")]
+    #[case(1023, false, None, "1023 B")]
+    fn test_format_bytes(
+        #[case] bytes: u64,
+        #[case] si: bool,
+        #[case] max_width: Option<usize>,
+        #[case] expected: &str,
+    ) {
+        let mut f = format_bytes(bytes).si(si);
+        if let Some(w) = max_width {
+            f = f.max_width(w);
+        }
+        let spans = f.into_spans();
+        assert_eq!(to_string(&spans), expected);
+        assert!(spans_width(&spans) <= max_width.unwrap_or(usize::MAX));
+    }
+
+    #[test]
+    fn test_format_bytes_styles() {
+        let actual = format_bytes(2048)
+            .number_fg(Color::White)
+            .number_bg(Color::Blue)
+            .unit_fg(Color::DarkGray)
+            .unit_bg(Color::Black)
+            .into_spans();
+        let expected = vec![
+            Span::raw("2.0").white().on_blue(),
+            Span::raw(" "),
+            Span::raw("KiB").dark_gray().on_black(),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case(Duration::from_millis(250), None, "250ms")]
+    #[case(Duration::from_secs(5), None, "5s")]
+    #[case(Duration::from_secs(3723), None, "1h 2m 3s")]
+    #[case(Duration::from_secs(3720), None, "1h 2m")]
+    #[case(Duration::from_secs(90061), None, "1d 1h 1m 1s")]
+    #[case(Duration::from_secs(3723), Some(5), "1h 2m")]
+    #[case(Duration::from_secs(3723), Some(2), "1h")]
+    #[case(Duration::from_secs(3723), Some(1), "1")]
+    #[case(Duration::from_secs(3600), Some(0), "")]
+    fn test_format_duration(
+        #[case] duration: Duration,
+        #[case] max_width: Option<usize>,
+        #[case] expected: &str,
+    ) {
+        let mut f = format_duration(duration);
+        if let Some(w) = max_width {
+            f = f.max_width(w);
+        }
+        let spans = f.into_spans();
+        assert_eq!(to_string(&spans), expected);
+        assert!(spans_width(&spans) <= max_width.unwrap_or(usize::MAX));
+    }
+
+    #[rstest]
+    #[case(-30, None, "just now")]
+    #[case(-30, Some(4), "now")]
+    #[case(-120, None, "2 minutes ago")]
+    #[case(-60, None, "1 minute ago")]
+    #[case(-120, Some(10), "2 min ago")]
+    #[case(-120, Some(7), "2m ago")]
+    #[case(-120, Some(3), "-2m")]
+    #[case(-120, Some(2), "-2")]
+    #[case(-7200, None, "2 hours ago")]
+    #[case(-3 * 86400, None, "3 days ago")]
+    #[case(-14 * 86400, Some(6), "2w ago")]
+    #[case(-60 * 86400, None, "2 months ago")]
+    #[case(-400 * 86400, None, "1 year ago")]
+    #[case(300, None, "in 5 minutes")]
+    #[case(300, Some(5), "in 5m")]
+    #[case(300, Some(3), "+5m")]
+    #[case(300, Some(0), "")]
+    fn test_format_relative_time(
+        #[case] offset_secs: i64,
+        #[case] max_width: Option<usize>,
+        #[case] expected: &str,
+    ) {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        let time = if offset_secs < 0 {
+            now - Duration::from_secs(offset_secs.unsigned_abs())
+        } else {
+            now + Duration::from_secs(offset_secs as u64)
+        };
+        let mut f = format_relative_time(time).now(now);
+        if let Some(w) = max_width {
+            f = f.max_width(w);
+        }
+        let spans = f.into_spans();
+        assert_eq!(to_string(&spans), expected);
+        assert!(spans_width(&spans) <= max_width.unwrap_or(usize::MAX));
+    }
+
+    #[test]
+    fn test_format_relative_time_styles() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let actual = format_relative_time(now - Duration::from_secs(180))
+            .now(now)
+            .number_modifier(Modifier::BOLD)
+            .unit_fg(Color::Gray)
+            .max_width(6)
+            .into_spans();
+        let expected = vec![
+            Span::raw("3").bold(),
+            Span::raw("m").gray(),
+            Span::raw(" ago"),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case(0, None, "0")]
+    #[case(999, None, "999")]
+    #[case(1234, None, "1,234")]
+    #[case(1234567, None, "1,234,567")]
+    #[case(1234, Some(4), "1.2k")]
+    #[case(1234567, Some(4), "1.2M")]
+    #[case(123456, Some(4), "123k")]
+    #[case(12345, Some(3), "12k")]
+    #[case(999_999, Some(5), "1.0M")]
+    #[case(999_999, Some(2), "1M")]
+    #[case(1234567, Some(1), "1")]
+    #[case(999_499, Some(5), "999k")]
+    #[case(99_960, Some(5), "100k")]
+    #[case(99_940, Some(5), "99.9k")]
+    fn test_format_count(
+        #[case] count: u64,
+        #[case] max_width: Option<usize>,
+        #[case] expected: &str,
+    ) {
+        let mut f = format_count(count);
+        if let Some(w) = max_width {
+            f = f.max_width(w);
+        }
+        let spans = f.into_spans();
+        assert_eq!(to_string(&spans), expected);
+        assert!(spans_width(&spans) <= max_width.unwrap_or(usize::MAX));
+    }
+}
@@ -3,6 +3,8 @@ use ratatui_core::{
     text::Span,
 };
 
+use crate::text::StyledText;
+
 pub fn highlight_matched_text<'a, T>(t: T) -> HigilightMatchedText<'a>
 where
     T: Into<Vec<Span<'a>>>,
@@ -34,7 +36,7 @@ impl Range {
     }
 }
 
-impl HigilightMatchedText<'_> {
+impl<'a> HigilightMatchedText<'a> {
     pub fn matched_indices(mut self, indices: Vec<usize>) -> Self {
         self.matches = to_ranges(indices);
         self
@@ -90,7 +92,7 @@ impl HigilightMatchedText<'_> {
         self
     }
 
-    pub fn into_spans(self) -> Vec<Span<'static>> {
+    pub fn into_spans(self) -> Vec<Span<'a>> {
         if self.spans.is_empty() {
             return vec![];
         }
@@ -127,62 +129,31 @@ impl HigilightMatchedText<'_> {
             (self.matches.clone(), total_len, None)
         };
 
-        let mut result_spans = Vec::new();
-        let mut current_pos = 0;
-
-        for span in &self.spans {
-            if current_pos >= limit {
-                break;
-            }
-            let span_len = span.content.len();
-            let effective_span_end = (current_pos + span_len).min(limit);
-
-            let original_style = span.style;
-            let mut span_cursor = 0;
-
-            while current_pos + span_cursor < effective_span_end {
-                let current_abs_pos = current_pos + span_cursor;
-
-                let next_break = find_next_break(current_abs_pos, &matches_to_use)
-                    .unwrap_or(effective_span_end)
-                    .min(effective_span_end);
-
-                let end_in_span = next_break - current_pos;
-
-                let content_slice = &span.content[span_cursor..end_in_span];
-
-                if content_slice.is_empty() {
-                    span_cursor = end_in_span;
-                    continue;
-                }
-
-                let is_matched = matches_to_use
-                    .iter()
-                    .any(|r| r.start <= current_abs_pos && current_abs_pos < r.end);
-                let style = if is_matched {
-                    original_style.patch(self.matched_style)
-                } else {
-                    original_style.patch(self.not_matched_style)
-                };
-
-                result_spans.push(Span::styled(content_slice.to_string(), style));
-                span_cursor = end_in_span;
-            }
-            current_pos += span_len;
+        let ellipsis_base_style = self
+            .spans
+            .iter()
+            .scan(0, |pos, span| {
+                *pos += span.content.len();
+                Some((*pos, span.style))
+            })
+            .find(|(end, _)| *end > limit)
+            .map(|(_, style)| style)
+            .unwrap_or_default();
+
+        let mut styles = Vec::with_capacity(matches_to_use.len() * 2 + 1);
+        let mut pos = 0;
+        for r in &matches_to_use {
+            let (start, end) = (r.start.min(limit), r.end.min(limit));
+            styles.push((pos..start, self.not_matched_style));
+            styles.push((start..end, self.matched_style));
+            pos = pos.max(end);
         }
+        styles.push((pos..limit, self.not_matched_style));
 
+        let mut text = StyledText::new(self.spans);
+        text.truncate(limit);
+        let mut result_spans = text.patch_sorted_styles(styles).into_spans();
         if let Some(ellipsis) = ellipsis_s {
-            let mut ellipsis_base_style = Style::default();
-            let mut pos = 0;
-            for span in &self.spans {
-                let span_end = pos + span.content.len();
-                if span_end > limit {
-                    ellipsis_base_style = span.style;
-                    break;
-                }
-                pos = span_end;
-            }
-
             let ellipsis_start_pos = limit;
             let is_matched = matches_to_use
                 .iter()
@@ -199,14 +170,6 @@ impl HigilightMatchedText<'_> {
     }
 }
 
-fn find_next_break(pos: usize, matches: &[Range]) -> Option<usize> {
-    matches
-        .iter()
-        .flat_map(|r| [r.start, r.end])
-        .filter(|&b| b > pos)
-        .min()
-}
-
 fn to_ranges(indices: Vec<usize>) -> Vec<Range> {
     if indices.is_empty() {
         return Vec::new();
@@ -237,6 +200,8 @@ fn sort_and_dedup(mut indices: Vec<usize>) -> Vec<usize> {
 
 #[cfg(test)]
 mod tests {
+    use std::borrow::Cow;
+
     use ratatui_core::style::{Color, Modifier};
     use rstest::*;
 
@@ -496,4 +461,22 @@ mod tests {
         ];
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_highlight_matched_text_borrows_input() {
+        let s = String::from("abcdefghij");
+        let actual =
+            highlight_matched_text(vec![Span::raw(s.as_str()), Span::raw("xyz".to_string())])
+                .matched_range(2, 5)
+                .matched_fg(Color::Red)
+                .into_spans();
+        assert_eq!(actual.len(), 4);
+        for (span, offset) in actual[..3].iter().zip([0, 2, 5]) {
+            match span.content {
+                Cow::Borrowed(c) => assert_eq!(c.as_ptr(), s[offset..].as_ptr()),
+                Cow::Owned(_) => panic!("expected borrowed span"),
+            }
+        }
+        assert_eq!(actual[3], Span::raw("xyz"));
+    }
 }
@@ -0,0 +1,340 @@
+use ratatui_core::{
+    style::{Color, Modifier, Style},
+    text::Span,
+};
+use ratatui_crossterm::crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{
+    keys::{key_names, normalize_key, MODIFIER_NAMES},
+    spans::{spans_width, truncate_spans},
+};
+
+pub fn key_hints(hints: Vec<KeyHint<'_>>) -> KeyHints<'_> {
+    KeyHints {
+        hints,
+        separator: "  ",
+        key_separator: " ",
+        ..Default::default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyHint<'a> {
+    keys: Vec<KeyEvent>,
+    description: &'a str,
+    priority: u8,
+}
+
+impl<'a> KeyHint<'a> {
+    pub fn new(key: KeyEvent, description: &'a str) -> Self {
+        Self::keys(vec![key], description)
+    }
+
+    pub fn keys(keys: Vec<KeyEvent>, description: &'a str) -> Self {
+        Self {
+            keys,
+            description,
+            priority: 0,
+        }
+    }
+
+    pub fn priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum KeyNotation {
+    #[default]
+    Text,
+    Symbol,
+}
+
+#[derive(Default)]
+pub struct KeyHints<'a> {
+    hints: Vec<KeyHint<'a>>,
+    notation: KeyNotation,
+    key_style: Style,
+    description_style: Style,
+    brackets: (&'a str, &'a str),
+    separator: &'a str,
+    key_separator: &'a str,
+    max_width: Option<usize>,
+    ellipsis: &'a str,
+}
+
+impl<'a> KeyHints<'a> {
+    pub fn notation(mut self, notation: KeyNotation) -> Self {
+        self.notation = notation;
+        self
+    }
+
+    pub fn key_style(mut self, style: Style) -> Self {
+        self.key_style = style;
+        self
+    }
+
+    pub fn key_fg(mut self, color: Color) -> Self {
+        self.key_style = self.key_style.fg(color);
+        self
+    }
+
+    pub fn key_bg(mut self, color: Color) -> Self {
+        self.key_style = self.key_style.bg(color);
+        self
+    }
+
+    pub fn key_modifier(mut self, modifier: Modifier) -> Self {
+        self.key_style = self.key_style.add_modifier(modifier);
+        self
+    }
+
+    pub fn description_style(mut self, style: Style) -> Self {
+        self.description_style = style;
+        self
+    }
+
+    pub fn description_fg(mut self, color: Color) -> Self {
+        self.description_style = self.description_style.fg(color);
+        self
+    }
+
+    pub fn description_bg(mut self, color: Color) -> Self {
+        self.description_style = self.description_style.bg(color);
+        self
+    }
+
+    pub fn description_modifier(mut self, modifier: Modifier) -> Self {
+        self.description_style = self.description_style.add_modifier(modifier);
+        self
+    }
+
+    pub fn brackets(mut self, open: &'a str, close: &'a str) -> Self {
+        self.brackets = (open, close);
+        self
+    }
+
+    pub fn separator(mut self, separator: &'a str) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    pub fn key_separator(mut self, separator: &'a str) -> Self {
+        self.key_separator = separator;
+        self
+    }
+
+    pub fn max_width(mut self, max_width: usize) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    pub fn ellipsis(mut self, ellipsis: &'a str) -> Self {
+        self.ellipsis = ellipsis;
+        self
+    }
+
+    pub fn into_spans(self) -> Vec<Span<'a>> {
+        let rendered: Vec<Vec<Span<'a>>> = self.hints.iter().map(|h| self.hint_spans(h)).collect();
+
+        let Some(max_width) = self.max_width else {
+            return self.join(rendered);
+        };
+
+        let separator_width = console::measure_text_width(self.separator);
+        let hint_widths: Vec<usize> = rendered.iter().map(|s| spans_width(s)).collect();
+
+        let mut visible = vec![true; rendered.len()];
+        let mut drop_order: Vec<usize> = (0..rendered.len()).collect();
+        drop_order.sort_by_key(|&i| (self.hints[i].priority, usize::MAX - i));
+
+        let mut total_width =
+            hint_widths.iter().sum::<usize>() + separator_width * rendered.len().saturating_sub(1);
+        let mut visible_count = rendered.len();
+        for i in drop_order {
+            if total_width <= max_width || visible_count <= 1 {
+                break;
+            }
+            visible[i] = false;
+            visible_count -= 1;
+            total_width -= hint_widths[i] + separator_width;
+        }
+
+        let rendered = rendered
+            .into_iter()
+            .zip(visible)
+            .filter_map(|(s, v)| v.then_some(s))
+            .collect();
+        truncate_spans(self.join(rendered), max_width)
+            .ellipsis(self.ellipsis)
+            .ellipsis_style(self.description_style)
+            .into_spans()
+    }
+
+    fn hint_spans(&self, hint: &KeyHint<'a>) -> Vec<Span<'a>> {
+        let keys: Vec<String> = hint
+            .keys
+            .iter()
+            .map(|k| key_display(k, self.notation))
+            .collect();
+        let (open, close) = self.brackets;
+        let key = format!("{open}{}{close}", keys.join("/"));
+        vec![
+            Span::styled(key, self.key_style),
+            Span::styled(self.key_separator, self.description_style),
+            Span::styled(hint.description, self.description_style),
+        ]
+    }
+
+    fn join(&self, rendered: Vec<Vec<Span<'a>>>) -> Vec<Span<'a>> {
+        let mut spans = Vec::new();
+        for (i, hint) in rendered.into_iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::styled(self.separator, self.description_style));
+            }
+            spans.extend(hint);
+        }
+        spans
+    }
+}
+
+pub fn key_display(key: &KeyEvent, notation: KeyNotation) -> String {
+    let (code, modifiers) = normalize_key(key);
+    let name = match (code, key_names(code)) {
+        (_, Some((text, symbol))) => match notation {
+            KeyNotation::Text => text.to_string(),
+            KeyNotation::Symbol => symbol.to_string(),
+        },
+        (KeyCode::Char(c), None) => c.to_string(),
+        (KeyCode::F(n), None) => format!("F{n}"),
+        (code, None) => code.to_string(),
+    };
+
+    let mut s = String::new();
+    for (m, text, symbol) in MODIFIER_NAMES {
+        if modifiers.contains(*m) {
+            s.push_str(match notation {
+                KeyNotation::Text => text,
+                KeyNotation::Symbol => symbol,
+            });
+        }
+    }
+    s.push_str(&name);
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui_crossterm::crossterm::event::KeyModifiers;
+    use rstest::*;
+
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn hints() -> Vec<KeyHint<'static>> {
+        vec![
+            KeyHint::new(key(KeyCode::Char('q'), KeyModifiers::NONE), "Quit").priority(2),
+            KeyHint::new(key(KeyCode::Char('/'), KeyModifiers::NONE), "Search").priority(1),
+            KeyHint::new(key(KeyCode::Char('d'), KeyModifiers::CONTROL), "Page down"),
+        ]
+    }
+
+    fn content(spans: &[Span<'_>]) -> String {
+        spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[rstest]
+    #[case(key(KeyCode::Char('d'), KeyModifiers::CONTROL), "C-d", "⌃d")]
+    #[case(key(KeyCode::Char('G'), KeyModifiers::SHIFT), "G", "G")]
+    #[case(key(KeyCode::BackTab, KeyModifiers::SHIFT), "S-Tab", "⇧⇥")]
+    #[case(key(KeyCode::Enter, KeyModifiers::NONE), "CR", "⏎")]
+    #[case(key(KeyCode::Insert, KeyModifiers::NONE), "Insert", "Ins")]
+    #[case(key(KeyCode::PageDown, KeyModifiers::NONE), "PageDown", "⇟")]
+    #[case(key(KeyCode::Up, KeyModifiers::CONTROL | KeyModifiers::SHIFT), "C-S-Up", "⌃⇧↑")]
+    #[case(key(KeyCode::Char('x'), KeyModifiers::ALT), "A-x", "⌥x")]
+    #[case(key(KeyCode::Char(' '), KeyModifiers::NONE), "Space", "␣")]
+    #[case(key(KeyCode::F(5), KeyModifiers::NONE), "F5", "F5")]
+    fn test_key_display(#[case] key: KeyEvent, #[case] text: &str, #[case] symbol: &str) {
+        assert_eq!(key_display(&key, KeyNotation::Text), text);
+        assert_eq!(key_display(&key, KeyNotation::Symbol), symbol);
+    }
+
+    #[test]
+    fn test_key_hints() {
+        let key_style = Style::default().fg(Color::Yellow);
+        let description_style = Style::default().fg(Color::Gray);
+        let actual = key_hints(hints())
+            .brackets("[", "]")
+            .key_style(key_style)
+            .description_style(description_style)
+            .into_spans();
+        let expected = vec![
+            Span::styled("[q]", key_style),
+            Span::styled(" ", description_style),
+            Span::styled("Quit", description_style),
+            Span::styled("  ", description_style),
+            Span::styled("[/]", key_style),
+            Span::styled(" ", description_style),
+            Span::styled("Search", description_style),
+            Span::styled("  ", description_style),
+            Span::styled("[C-d]", key_style),
+            Span::styled(" ", description_style),
+            Span::styled("Page down", description_style),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_key_hints_multiple_keys() {
+        let hints = vec![KeyHint::keys(
+            vec![
+                key(KeyCode::Char('j'), KeyModifiers::NONE),
+                key(KeyCode::Down, KeyModifiers::NONE),
+            ],
+            "Down",
+        )];
+        let actual = key_hints(hints.clone()).into_spans();
+        assert_eq!(content(&actual), "j/Down Down");
+        let actual = key_hints(hints).notation(KeyNotation::Symbol).into_spans();
+        assert_eq!(content(&actual), "j/↓ Down");
+    }
+
+    #[rstest]
+    #[case(100, "[q] Quit  [/] Search  [C-d] Page down")]
+    #[case(37, "[q] Quit  [/] Search  [C-d] Page down")]
+    #[case(36, "[q] Quit  [/] Search")]
+    #[case(20, "[q] Quit  [/] Search")]
+    #[case(19, "[q] Quit")]
+    #[case(8, "[q] Quit")]
+    #[case(7, "[q] Q..")]
+    #[case(2, "..")]
+    fn test_key_hints_max_width(#[case] max_width: usize, #[case] expected: &str) {
+        let actual = key_hints(hints())
+            .brackets("[", "]")
+            .max_width(max_width)
+            .ellipsis("..")
+            .into_spans();
+        assert_eq!(content(&actual), expected);
+    }
+
+    #[test]
+    fn test_key_hints_max_width_keeps_order() {
+        let hints = vec![
+            KeyHint::new(key(KeyCode::Char('a'), KeyModifiers::NONE), "A").priority(1),
+            KeyHint::new(key(KeyCode::Char('b'), KeyModifiers::NONE), "B"),
+            KeyHint::new(key(KeyCode::Char('c'), KeyModifiers::NONE), "C").priority(1),
+            KeyHint::new(key(KeyCode::Char('d'), KeyModifiers::NONE), "D"),
+        ];
+        let actual = key_hints(hints.clone())
+            .separator(" ")
+            .max_width(11)
+            .into_spans();
+        assert_eq!(content(&actual), "a A b B c C");
+        let actual = key_hints(hints).separator(" ").max_width(10).into_spans();
+        assert_eq!(content(&actual), "a A c C");
+    }
+}
@@ -1,8 +1,31 @@
+use std::{
+    error::Error,
+    fmt,
+    io::{self, Write},
+};
+
+use ratatui_core::layout::{Position, Rect};
+use ratatui_crossterm::crossterm::{
+    event::{
+        Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers,
+        KeyboardEnhancementFlags, MouseEvent, PopKeyboardEnhancementFlags,
+        PushKeyboardEnhancementFlags,
+    },
+    execute, terminal,
+};
+
 #[macro_export]
 macro_rules! key_code {
     ( $code:pat ) => {
         ratatui_crossterm::crossterm::event::KeyEvent { code: $code, .. }
     };
+    ( $code:pat, $kind:ident ) => {
+        ratatui_crossterm::crossterm::event::KeyEvent {
+            code: $code,
+            kind: ratatui_crossterm::crossterm::event::KeyEventKind::$kind,
+            ..
+        }
+    };
 }
 
 #[macro_export]
@@ -26,11 +49,610 @@ macro_rules! key_code_char {
             ..
         }
     };
+    ( $c:expr, Ctrl, $kind:ident ) => {
+        ratatui_crossterm::crossterm::event::KeyEvent {
+            code: ratatui_crossterm::crossterm::event::KeyCode::Char($c),
+            modifiers: ratatui_crossterm::crossterm::event::KeyModifiers::CONTROL,
+            kind: ratatui_crossterm::crossterm::event::KeyEventKind::$kind,
+            ..
+        }
+    };
+    ( $c:expr, $kind:ident ) => {
+        ratatui_crossterm::crossterm::event::KeyEvent {
+            code: ratatui_crossterm::crossterm::event::KeyCode::Char($c),
+            kind: ratatui_crossterm::crossterm::event::KeyEventKind::$kind,
+            ..
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! key_code_media {
+    ( $code:pat ) => {
+        ratatui_crossterm::crossterm::event::KeyEvent {
+            code: ratatui_crossterm::crossterm::event::KeyCode::Media($code),
+            ..
+        }
+    };
+    ( $code:pat, $kind:ident ) => {
+        ratatui_crossterm::crossterm::event::KeyEvent {
+            code: ratatui_crossterm::crossterm::event::KeyCode::Media($code),
+            kind: ratatui_crossterm::crossterm::event::KeyEventKind::$kind,
+            ..
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! mouse_event {
+    ( $kind:pat ) => {
+        ratatui_crossterm::crossterm::event::MouseEvent { kind: $kind, .. }
+    };
+    ( $kind:pat, Ctrl ) => {
+        ratatui_crossterm::crossterm::event::MouseEvent {
+            kind: $kind,
+            modifiers: ratatui_crossterm::crossterm::event::KeyModifiers::CONTROL,
+            ..
+        }
+    };
+    ( $kind:pat, Shift ) => {
+        ratatui_crossterm::crossterm::event::MouseEvent {
+            kind: $kind,
+            modifiers: ratatui_crossterm::crossterm::event::KeyModifiers::SHIFT,
+            ..
+        }
+    };
+    ( $kind:pat, Alt ) => {
+        ratatui_crossterm::crossterm::event::MouseEvent {
+            kind: $kind,
+            modifiers: ratatui_crossterm::crossterm::event::KeyModifiers::ALT,
+            ..
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! mouse_click {
+    ( $button:ident $(, $m:ident )? ) => {
+        $crate::mouse_event!(
+            ratatui_crossterm::crossterm::event::MouseEventKind::Down(
+                ratatui_crossterm::crossterm::event::MouseButton::$button
+            )
+            $(, $m )?
+        )
+    };
+}
+
+#[macro_export]
+macro_rules! mouse_up {
+    ( $button:ident $(, $m:ident )? ) => {
+        $crate::mouse_event!(
+            ratatui_crossterm::crossterm::event::MouseEventKind::Up(
+                ratatui_crossterm::crossterm::event::MouseButton::$button
+            )
+            $(, $m )?
+        )
+    };
+}
+
+#[macro_export]
+macro_rules! mouse_drag {
+    ( $button:ident $(, $m:ident )? ) => {
+        $crate::mouse_event!(
+            ratatui_crossterm::crossterm::event::MouseEventKind::Drag(
+                ratatui_crossterm::crossterm::event::MouseButton::$button
+            )
+            $(, $m )?
+        )
+    };
+}
+
+#[macro_export]
+macro_rules! mouse_scroll {
+    ( Up $(, $m:ident )? ) => {
+        $crate::mouse_event!(
+            ratatui_crossterm::crossterm::event::MouseEventKind::ScrollUp
+            $(, $m )?
+        )
+    };
+    ( Down $(, $m:ident )? ) => {
+        $crate::mouse_event!(
+            ratatui_crossterm::crossterm::event::MouseEventKind::ScrollDown
+            $(, $m )?
+        )
+    };
+    ( Left $(, $m:ident )? ) => {
+        $crate::mouse_event!(
+            ratatui_crossterm::crossterm::event::MouseEventKind::ScrollLeft
+            $(, $m )?
+        )
+    };
+    ( Right $(, $m:ident )? ) => {
+        $crate::mouse_event!(
+            ratatui_crossterm::crossterm::event::MouseEventKind::ScrollRight
+            $(, $m )?
+        )
+    };
+}
+
+pub fn mouse_position(e: &MouseEvent) -> Position {
+    Position::new(e.column, e.row)
+}
+
+pub fn mouse_in_rect(e: &MouseEvent, area: Rect) -> bool {
+    area.contains(mouse_position(e))
+}
+
+pub fn mouse_position_in_rect(e: &MouseEvent, area: Rect) -> Option<Position> {
+    if mouse_in_rect(e, area) {
+        Some(Position::new(e.column - area.x, e.row - area.y))
+    } else {
+        None
+    }
+}
+
+pub fn is_press(e: &KeyEvent) -> bool {
+    e.kind == KeyEventKind::Press
+}
+
+pub fn is_repeat(e: &KeyEvent) -> bool {
+    e.kind == KeyEventKind::Repeat
+}
+
+pub fn is_release(e: &KeyEvent) -> bool {
+    e.kind == KeyEventKind::Release
+}
+
+pub fn is_press_or_repeat(e: &KeyEvent) -> bool {
+    matches!(e.kind, KeyEventKind::Press | KeyEventKind::Repeat)
+}
+
+pub fn is_keypad(e: &KeyEvent) -> bool {
+    e.state.contains(KeyEventState::KEYPAD)
+}
+
+pub fn is_media(e: &KeyEvent) -> bool {
+    matches!(e.code, KeyCode::Media(_))
+}
+
+pub fn enable_keyboard_enhancement<W: Write>(
+    w: &mut W,
+    flags: KeyboardEnhancementFlags,
+) -> io::Result<()> {
+    execute!(w, PushKeyboardEnhancementFlags(flags))
+}
+
+pub fn disable_keyboard_enhancement<W: Write>(w: &mut W) -> io::Result<()> {
+    execute!(w, PopKeyboardEnhancementFlags)
+}
+
+pub fn supports_keyboard_enhancement() -> io::Result<bool> {
+    terminal::supports_keyboard_enhancement()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeymapResult<A> {
+    Pending,
+    Cancelled,
+    Matched(KeymapMatch<A>),
+    Unmatched,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeymapMatch<A> {
+    pub action: A,
+    pub operator: Option<A>,
+    pub count: Option<usize>,
+}
+
+impl<A> KeymapMatch<A> {
+    pub fn count_or(&self, default: usize) -> usize {
+        self.count.unwrap_or(default)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VimKeymap<A> {
+    actions: Vec<(KeyCode, KeyModifiers, A)>,
+    operators: Vec<(KeyCode, KeyModifiers, A)>,
+    max_count: usize,
+    count: Option<usize>,
+    operator: Option<(KeyCode, KeyModifiers, A, Option<usize>)>,
+}
+
+impl<A> Default for VimKeymap<A> {
+    fn default() -> Self {
+        Self {
+            actions: Vec::new(),
+            operators: Vec::new(),
+            max_count: usize::MAX,
+            count: None,
+            operator: None,
+        }
+    }
+}
+
+impl<A: Clone> VimKeymap<A> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn action(mut self, key: KeyEvent, action: A) -> Self {
+        let (code, modifiers) = normalize_key(&key);
+        self.actions.push((code, modifiers, action));
+        self
+    }
+
+    pub fn operator(mut self, key: KeyEvent, operator: A) -> Self {
+        let (code, modifiers) = normalize_key(&key);
+        self.operators.push((code, modifiers, operator));
+        self
+    }
+
+    pub fn max_count(mut self, max_count: usize) -> Self {
+        self.max_count = max_count;
+        self
+    }
+
+    pub fn pending_count(&self) -> Option<usize> {
+        self.count
+    }
+
+    pub fn pending_operator(&self) -> Option<&A> {
+        self.operator.as_ref().map(|(_, _, op, _)| op)
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.count.is_some() || self.operator.is_some()
+    }
+
+    pub fn reset(&mut self) {
+        self.count = None;
+        self.operator = None;
+    }
+
+    pub fn handle_key(&mut self, key: &KeyEvent) -> KeymapResult<A> {
+        if !is_press_or_repeat(key) {
+            return KeymapResult::Unmatched;
+        }
+
+        if let Some(d) = self.count_digit(key) {
+            let count = self.count.unwrap_or(0);
+            let count = count.saturating_mul(10).saturating_add(d);
+            self.count = Some(count.min(self.max_count));
+            return KeymapResult::Pending;
+        }
+
+        if key.code == KeyCode::Esc && self.is_pending() {
+            self.reset();
+            return KeymapResult::Cancelled;
+        }
+
+        let count = self.count.take();
+
+        if let Some((code, modifiers, operator, op_count)) = self.operator.take() {
+            let count = multiply_count(op_count, count, self.max_count);
+            let action = if normalize_key(key) == (code, modifiers) {
+                Some(operator.clone())
+            } else {
+                find_binding(&self.actions, key)
+            };
+            return match action {
+                Some(action) => KeymapResult::Matched(KeymapMatch {
+                    action,
+                    operator: Some(operator),
+                    count,
+                }),
+                None => KeymapResult::Unmatched,
+            };
+        }
+
+        if let Some(operator) = find_binding(&self.operators, key) {
+            let (code, modifiers) = normalize_key(key);
+            self.operator = Some((code, modifiers, operator, count));
+            return KeymapResult::Pending;
+        }
+
+        match find_binding(&self.actions, key) {
+            Some(action) => KeymapResult::Matched(KeymapMatch {
+                action,
+                operator: None,
+                count,
+            }),
+            None => KeymapResult::Unmatched,
+        }
+    }
+
+    fn count_digit(&self, key: &KeyEvent) -> Option<usize> {
+        match key.code {
+            KeyCode::Char(c) if key.modifiers == KeyModifiers::NONE => match c.to_digit(10) {
+                Some(0) if self.count.is_none() => None,
+                d => d.map(|d| d as usize),
+            },
+            _ => None,
+        }
+    }
+}
+
+fn find_binding<A: Clone>(bindings: &[(KeyCode, KeyModifiers, A)], key: &KeyEvent) -> Option<A> {
+    let key = normalize_key(key);
+    bindings
+        .iter()
+        .find(|(code, modifiers, _)| (*code, *modifiers) == key)
+        .map(|(_, _, a)| a.clone())
+}
+
+fn multiply_count(a: Option<usize>, b: Option<usize>, max: usize) -> Option<usize> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.saturating_mul(b).min(max)),
+        (a, b) => a.or(b),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseKeysError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl ParseKeysError {
+    fn new(position: usize, message: impl Into<String>) -> Self {
+        Self {
+            position,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseKeysError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl Error for ParseKeysError {}
+
+pub fn parse_keys(script: &str) -> Result<Vec<Event>, ParseKeysError> {
+    let mut events = Vec::new();
+    let mut chars = script.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '<' => {
+                let mut name = String::new();
+                let mut closed = false;
+                for (_, c) in chars.by_ref() {
+                    if c == '>' {
+                        closed = true;
+                        break;
+                    }
+                    name.push(c);
+                }
+                if !closed {
+                    return Err(ParseKeysError::new(i, "unclosed '<'"));
+                }
+                let key = parse_key_notation(&name)
+                    .ok_or_else(|| ParseKeysError::new(i, format!("unknown key <{name}>")))?;
+                events.push(Event::Key(key));
+            }
+            c => events.push(Event::Key(char_key_event(c, KeyModifiers::NONE))),
+        }
+    }
+    Ok(events)
+}
+
+pub fn replay_keys<F>(script: &str, mut handler: F) -> Result<(), ParseKeysError>
+where
+    F: FnMut(Event),
+{
+    for event in parse_keys(script)? {
+        handler(event);
+    }
+    Ok(())
+}
+
+fn parse_key_notation(name: &str) -> Option<KeyEvent> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = name;
+    while rest.len() > 2 && rest.as_bytes()[1] == b'-' {
+        modifiers |= match rest.as_bytes()[0].to_ascii_uppercase() {
+            b'C' => KeyModifiers::CONTROL,
+            b'S' => KeyModifiers::SHIFT,
+            b'A' | b'M' => KeyModifiers::ALT,
+            b'D' => KeyModifiers::SUPER,
+            _ => return None,
+        };
+        rest = &rest[2..];
+    }
+
+    let mut chars = rest.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(char_key_event(c, modifiers));
+    }
+
+    let code = match rest.to_ascii_lowercase().as_str() {
+        "cr" | "enter" | "return" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+        "tab" => KeyCode::Tab,
+        "bs" | "backspace" => KeyCode::Backspace,
+        "del" | "delete" => KeyCode::Delete,
+        "ins" | "insert" => KeyCode::Insert,
+        "space" => KeyCode::Char(' '),
+        "lt" => KeyCode::Char('<'),
+        "gt" => KeyCode::Char('>'),
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        s => match s.strip_prefix('f').and_then(|n| n.parse().ok()) {
+            Some(n) if (1..=24).contains(&n) => KeyCode::F(n),
+            _ => return None,
+        },
+    };
+    Some(KeyEvent::new(code, modifiers))
+}
+
+fn char_key_event(c: char, mut modifiers: KeyModifiers) -> KeyEvent {
+    if c.is_uppercase() {
+        modifiers |= KeyModifiers::SHIFT;
+    }
+    KeyEvent::new(KeyCode::Char(c), modifiers)
+}
+
+pub(crate) const KEY_NAMES: &[(KeyCode, &str, &str)] = &[
+    (KeyCode::Char(' '), "Space", "␣"),
+    (KeyCode::Enter, "CR", "⏎"),
+    (KeyCode::Esc, "Esc", "⎋"),
+    (KeyCode::Tab, "Tab", "⇥"),
+    (KeyCode::Backspace, "BS", "⌫"),
+    (KeyCode::Delete, "Del", "⌦"),
+    (KeyCode::Insert, "Insert", "Ins"),
+    (KeyCode::Up, "Up", "↑"),
+    (KeyCode::Down, "Down", "↓"),
+    (KeyCode::Left, "Left", "←"),
+    (KeyCode::Right, "Right", "→"),
+    (KeyCode::Home, "Home", "↖"),
+    (KeyCode::End, "End", "↘"),
+    (KeyCode::PageUp, "PageUp", "⇞"),
+    (KeyCode::PageDown, "PageDown", "⇟"),
+];
+
+pub(crate) const MODIFIER_NAMES: &[(KeyModifiers, &str, &str)] = &[
+    (KeyModifiers::CONTROL, "C-", "⌃"),
+    (KeyModifiers::SHIFT, "S-", "⇧"),
+    (KeyModifiers::ALT, "A-", "⌥"),
+    (KeyModifiers::SUPER, "D-", "⌘"),
+];
+
+pub(crate) fn key_names(code: KeyCode) -> Option<(&'static str, &'static str)> {
+    KEY_NAMES
+        .iter()
+        .find(|(c, _, _)| *c == code)
+        .map(|(_, text, symbol)| (*text, *symbol))
+}
+
+pub(crate) fn normalize_key(e: &KeyEvent) -> (KeyCode, KeyModifiers) {
+    let mut modifiers = e.modifiers;
+    let code = match e.code {
+        KeyCode::Char(c) if c.is_uppercase() => {
+            modifiers.remove(KeyModifiers::SHIFT);
+            e.code
+        }
+        KeyCode::BackTab => {
+            modifiers.insert(KeyModifiers::SHIFT);
+            KeyCode::Tab
+        }
+        code => code,
+    };
+    (code, modifiers)
+}
+
+pub fn to_key_notation(e: &KeyEvent) -> Option<String> {
+    let (code, modifiers) = normalize_key(e);
+    let supported = MODIFIER_NAMES
+        .iter()
+        .fold(KeyModifiers::NONE, |acc, (m, _, _)| acc | *m);
+    if !supported.contains(modifiers) {
+        return None;
+    }
+    let code = match code {
+        KeyCode::Char('\t') => KeyCode::Tab,
+        KeyCode::Char('\n' | '\r') => KeyCode::Enter,
+        code => code,
+    };
+    let name = match code {
+        KeyCode::Char('<') => "lt".to_string(),
+        KeyCode::Char(c) if !c.is_whitespace() && modifiers.is_empty() => {
+            return Some(c.to_string())
+        }
+        KeyCode::Char('>') => "gt".to_string(),
+        KeyCode::Char(c) if !c.is_whitespace() => c.to_string(),
+        KeyCode::F(n) => format!("F{n}"),
+        code => key_names(code)?.0.to_string(),
+    };
+
+    let mut s = String::from("<");
+    for (m, prefix, _) in MODIFIER_NAMES {
+        if modifiers.contains(*m) {
+            s.push_str(prefix);
+        }
+    }
+    s.push_str(&name);
+    s.push('>');
+    Some(s)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedKeyError {
+    pub index: usize,
+    pub key: KeyEvent,
+}
+
+impl fmt::Display for UnsupportedKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unsupported key {} at index {}",
+            self.key.code, self.index
+        )
+    }
+}
+
+impl Error for UnsupportedKeyError {}
+
+#[derive(Debug, Default, Clone)]
+pub struct KeyRecorder {
+    keys: Vec<KeyEvent>,
+}
+
+impl KeyRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, event: &Event) {
+        if let Event::Key(key) = event {
+            if is_press_or_repeat(key) {
+                self.keys.push(*key);
+            }
+        }
+    }
+
+    pub fn keys(&self) -> &[KeyEvent] {
+        &self.keys
+    }
+
+    pub fn clear(&mut self) {
+        self.keys.clear();
+    }
+
+    pub fn to_script(&self) -> Result<String, UnsupportedKeyError> {
+        let names = self
+            .keys
+            .iter()
+            .enumerate()
+            .map(|(index, key)| {
+                to_key_notation(key).ok_or(UnsupportedKeyError { index, key: *key })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(names.join(" "))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use ratatui_crossterm::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use ratatui_crossterm::crossterm::event::{
+        KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, MediaKeyCode, MouseButton,
+        MouseEvent, MouseEventKind,
+    };
+    use rstest::*;
+
+    use super::*;
 
     #[test]
     fn test_key_code() {
@@ -71,4 +693,509 @@ mod tests {
             panic!()
         }
     }
+
+    #[test]
+    fn test_key_code_kind() {
+        let e = KeyEvent::new_with_kind(KeyCode::Esc, KeyModifiers::NONE, KeyEventKind::Press);
+        assert!(matches!(e, key_code!(KeyCode::Esc)));
+        assert!(matches!(e, key_code!(KeyCode::Esc, Press)));
+        assert!(!matches!(e, key_code!(KeyCode::Esc, Release)));
+
+        let e = KeyEvent::new_with_kind(KeyCode::Esc, KeyModifiers::NONE, KeyEventKind::Release);
+        assert!(matches!(e, key_code!(KeyCode::Esc)));
+        assert!(!matches!(e, key_code!(KeyCode::Esc, Press)));
+        assert!(matches!(e, key_code!(KeyCode::Esc, Release)));
+
+        let e = KeyEvent::new_with_kind(KeyCode::Up, KeyModifiers::NONE, KeyEventKind::Repeat);
+        assert!(matches!(e, key_code!(KeyCode::Up, Repeat)));
+        assert!(!matches!(e, key_code!(KeyCode::Down, Repeat)));
+    }
+
+    #[test]
+    fn test_key_code_char_kind() {
+        let e =
+            KeyEvent::new_with_kind(KeyCode::Char('a'), KeyModifiers::NONE, KeyEventKind::Press);
+        assert!(matches!(e, key_code_char!('a', Press)));
+        assert!(!matches!(e, key_code_char!('a', Release)));
+        assert!(!matches!(e, key_code_char!('a', Ctrl, Press)));
+
+        let e = KeyEvent::new_with_kind(
+            KeyCode::Char('a'),
+            KeyModifiers::CONTROL,
+            KeyEventKind::Release,
+        );
+        assert!(matches!(e, key_code_char!('a', Release)));
+        assert!(matches!(e, key_code_char!('a', Ctrl, Release)));
+        assert!(!matches!(e, key_code_char!('a', Ctrl, Press)));
+        assert!(matches!(e, key_code_char!('a', Ctrl)));
+    }
+
+    #[test]
+    fn test_key_code_media() {
+        let e = KeyEvent::new(KeyCode::Media(MediaKeyCode::Play), KeyModifiers::NONE);
+        assert!(matches!(e, key_code_media!(MediaKeyCode::Play)));
+        assert!(matches!(e, key_code_media!(MediaKeyCode::Play, Press)));
+        assert!(!matches!(e, key_code_media!(MediaKeyCode::Pause)));
+        assert!(is_media(&e));
+
+        let e = KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE);
+        assert!(!is_media(&e));
+    }
+
+    #[test]
+    fn test_key_event_kind_helpers() {
+        let e = KeyEvent::new_with_kind(KeyCode::Enter, KeyModifiers::NONE, KeyEventKind::Press);
+        assert!(is_press(&e));
+        assert!(!is_repeat(&e));
+        assert!(!is_release(&e));
+        assert!(is_press_or_repeat(&e));
+
+        let e = KeyEvent::new_with_kind(KeyCode::Enter, KeyModifiers::NONE, KeyEventKind::Repeat);
+        assert!(!is_press(&e));
+        assert!(is_repeat(&e));
+        assert!(is_press_or_repeat(&e));
+
+        let e = KeyEvent::new_with_kind(KeyCode::Enter, KeyModifiers::NONE, KeyEventKind::Release);
+        assert!(is_release(&e));
+        assert!(!is_press_or_repeat(&e));
+    }
+
+    #[test]
+    fn test_is_keypad() {
+        let e = KeyEvent::new_with_kind_and_state(
+            KeyCode::Char('1'),
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+            KeyEventState::KEYPAD | KeyEventState::NUM_LOCK,
+        );
+        assert!(is_keypad(&e));
+        assert!(matches!(e, key_code_char!('1', Press)));
+
+        let e = KeyEvent::new(KeyCode::Char('1'), KeyModifiers::NONE);
+        assert!(!is_keypad(&e));
+    }
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> Event {
+        Event::Key(KeyEvent::new(code, modifiers))
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        let actual = parse_keys("j j <C-d> /foo<CR>").unwrap();
+        let expected = vec![
+            key(KeyCode::Char('j'), KeyModifiers::NONE),
+            key(KeyCode::Char('j'), KeyModifiers::NONE),
+            key(KeyCode::Char('d'), KeyModifiers::CONTROL),
+            key(KeyCode::Char('/'), KeyModifiers::NONE),
+            key(KeyCode::Char('f'), KeyModifiers::NONE),
+            key(KeyCode::Char('o'), KeyModifiers::NONE),
+            key(KeyCode::Char('o'), KeyModifiers::NONE),
+            key(KeyCode::Enter, KeyModifiers::NONE),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case("G", key(KeyCode::Char('G'), KeyModifiers::SHIFT))]
+    #[case("<Space>", key(KeyCode::Char(' '), KeyModifiers::NONE))]
+    #[case("<lt>", key(KeyCode::Char('<'), KeyModifiers::NONE))]
+    #[case("<esc>", key(KeyCode::Esc, KeyModifiers::NONE))]
+    #[case("<S-Tab>", key(KeyCode::BackTab, KeyModifiers::SHIFT))]
+    #[case("<A-x>", key(KeyCode::Char('x'), KeyModifiers::ALT))]
+    #[case("<M-x>", key(KeyCode::Char('x'), KeyModifiers::ALT))]
+    #[case("<C-S-Up>", key(KeyCode::Up, KeyModifiers::CONTROL | KeyModifiers::SHIFT))]
+    #[case("<F12>", key(KeyCode::F(12), KeyModifiers::NONE))]
+    #[case("<C-->", key(KeyCode::Char('-'), KeyModifiers::CONTROL))]
+    fn test_parse_keys_notation(#[case] script: &str, #[case] expected: Event) {
+        assert_eq!(parse_keys(script).unwrap(), vec![expected]);
+    }
+
+    #[rstest]
+    #[case("<C-d", ParseKeysError::new(0, "unclosed '<'"))]
+    #[case("a <Foo>", ParseKeysError::new(2, "unknown key <Foo>"))]
+    #[case("<X-a>", ParseKeysError::new(0, "unknown key <X-a>"))]
+    #[case("<F0>", ParseKeysError::new(0, "unknown key <F0>"))]
+    fn test_parse_keys_error(#[case] script: &str, #[case] expected: ParseKeysError) {
+        assert_eq!(parse_keys(script).unwrap_err(), expected);
+    }
+
+    #[test]
+    fn test_replay_keys() {
+        let mut buf = String::new();
+        replay_keys("ab<BS>c", |e| match e {
+            Event::Key(key_code_char!(c)) => buf.push(c),
+            Event::Key(key_code!(KeyCode::Backspace)) => {
+                buf.pop();
+            }
+            _ => {}
+        })
+        .unwrap();
+        assert_eq!(buf, "ac");
+    }
+
+    #[test]
+    fn test_key_recorder() {
+        let mut recorder = KeyRecorder::new();
+        let script = "j j <C-d> / f o o <CR> G <Space> <lt> > <C-gt> <S-Tab> <A-F5>";
+        for e in parse_keys(script).unwrap() {
+            recorder.record(&e);
+        }
+        recorder.record(&Event::Key(KeyEvent::new_with_kind(
+            KeyCode::Char('x'),
+            KeyModifiers::NONE,
+            KeyEventKind::Release,
+        )));
+        recorder.record(&Event::FocusGained);
+        assert_eq!(recorder.to_script(), Ok(script.to_string()));
+        assert_eq!(recorder.keys().len(), 15);
+
+        recorder.clear();
+        assert_eq!(recorder.to_script(), Ok(String::new()));
+    }
+
+    #[test]
+    fn test_key_recorder_unsupported_key() {
+        let mut recorder = KeyRecorder::new();
+        let keys = [
+            KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE),
+            KeyEvent::new(KeyCode::CapsLock, KeyModifiers::NONE),
+        ];
+        for key in keys {
+            recorder.record(&Event::Key(key));
+        }
+        let err = recorder.to_script().unwrap_err();
+        assert_eq!(
+            err,
+            UnsupportedKeyError {
+                index: 1,
+                key: keys[1]
+            }
+        );
+        assert_eq!(err.to_string(), "unsupported key Caps Lock at index 1");
+    }
+
+    #[rstest]
+    #[case(KeyCode::Char('x'), KeyModifiers::META)]
+    #[case(KeyCode::Char('x'), KeyModifiers::CONTROL | KeyModifiers::HYPER)]
+    #[case(KeyCode::Enter, KeyModifiers::META)]
+    #[case(KeyCode::Char('\u{3000}'), KeyModifiers::NONE)]
+    fn test_to_key_notation_unsupported(#[case] code: KeyCode, #[case] modifiers: KeyModifiers) {
+        assert_eq!(to_key_notation(&KeyEvent::new(code, modifiers)), None);
+    }
+
+    #[rstest]
+    #[case(KeyCode::Char(' '), KeyModifiers::NONE, "<Space>")]
+    #[case(KeyCode::Char('\t'), KeyModifiers::NONE, "<Tab>")]
+    #[case(KeyCode::Char('\n'), KeyModifiers::NONE, "<CR>")]
+    #[case(KeyCode::Char('\t'), KeyModifiers::CONTROL, "<C-Tab>")]
+    #[case(KeyCode::Char('x'), KeyModifiers::SUPER, "<D-x>")]
+    fn test_key_recorder_round_trip(
+        #[case] code: KeyCode,
+        #[case] modifiers: KeyModifiers,
+        #[case] expected: &str,
+    ) {
+        let mut recorder = KeyRecorder::new();
+        recorder.record(&Event::Key(KeyEvent::new(code, modifiers)));
+        let script = recorder.to_script().unwrap();
+        assert_eq!(script, expected);
+
+        let mut replayed = KeyRecorder::new();
+        replay_keys(&script, |e| replayed.record(&e)).unwrap();
+        assert_eq!(replayed.to_script(), Ok(script));
+        assert_eq!(replayed.keys().len(), 1);
+    }
+
+    fn mouse(kind: MouseEventKind, column: u16, row: u16, modifiers: KeyModifiers) -> MouseEvent {
+        MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers,
+        }
+    }
+
+    #[test]
+    fn test_mouse_event() {
+        let e = mouse(MouseEventKind::Moved, 0, 0, KeyModifiers::NONE);
+        assert!(matches!(e, mouse_event!(MouseEventKind::Moved)));
+        assert!(!matches!(e, mouse_event!(MouseEventKind::Moved, Ctrl)));
+
+        let e = mouse(MouseEventKind::Moved, 0, 0, KeyModifiers::ALT);
+        assert!(matches!(e, mouse_event!(MouseEventKind::Moved)));
+        assert!(matches!(e, mouse_event!(MouseEventKind::Moved, Alt)));
+        assert!(!matches!(e, mouse_event!(MouseEventKind::Moved, Shift)));
+    }
+
+    #[test]
+    fn test_mouse_click() {
+        let e = mouse(
+            MouseEventKind::Down(MouseButton::Left),
+            1,
+            2,
+            KeyModifiers::NONE,
+        );
+        assert!(matches!(e, mouse_click!(Left)));
+        assert!(!matches!(e, mouse_click!(Right)));
+        assert!(!matches!(e, mouse_click!(Left, Ctrl)));
+        assert!(!matches!(e, mouse_up!(Left)));
+
+        let e = mouse(
+            MouseEventKind::Down(MouseButton::Right),
+            1,
+            2,
+            KeyModifiers::CONTROL,
+        );
+        assert!(matches!(e, mouse_click!(Right)));
+        assert!(matches!(e, mouse_click!(Right, Ctrl)));
+
+        let e = mouse(
+            MouseEventKind::Up(MouseButton::Middle),
+            1,
+            2,
+            KeyModifiers::NONE,
+        );
+        assert!(matches!(e, mouse_up!(Middle)));
+    }
+
+    #[test]
+    fn test_mouse_drag() {
+        let e = mouse(
+            MouseEventKind::Drag(MouseButton::Left),
+            1,
+            2,
+            KeyModifiers::SHIFT,
+        );
+        assert!(matches!(e, mouse_drag!(Left)));
+        assert!(matches!(e, mouse_drag!(Left, Shift)));
+        assert!(!matches!(e, mouse_drag!(Left, Alt)));
+        assert!(!matches!(e, mouse_click!(Left)));
+    }
+
+    #[test]
+    fn test_mouse_scroll() {
+        let e = mouse(MouseEventKind::ScrollUp, 0, 0, KeyModifiers::NONE);
+        assert!(matches!(e, mouse_scroll!(Up)));
+        assert!(!matches!(e, mouse_scroll!(Down)));
+
+        let e = mouse(MouseEventKind::ScrollDown, 0, 0, KeyModifiers::CONTROL);
+        assert!(matches!(e, mouse_scroll!(Down)));
+        assert!(matches!(e, mouse_scroll!(Down, Ctrl)));
+
+        let e = mouse(MouseEventKind::ScrollRight, 0, 0, KeyModifiers::NONE);
+        assert!(matches!(e, mouse_scroll!(Right)));
+        assert!(!matches!(e, mouse_scroll!(Left)));
+    }
+
+    #[rstest]
+    #[case(5, 3, Some(Position::new(0, 0)))]
+    #[case(14, 3, Some(Position::new(9, 0)))]
+    #[case(14, 6, Some(Position::new(9, 3)))]
+    #[case(15, 3, None)]
+    #[case(5, 7, None)]
+    #[case(4, 3, None)]
+    #[case(5, 2, None)]
+    fn test_mouse_position_in_rect(
+        #[case] column: u16,
+        #[case] row: u16,
+        #[case] expected: Option<Position>,
+    ) {
+        let area = Rect::new(5, 3, 10, 4);
+        let e = mouse(
+            MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            KeyModifiers::NONE,
+        );
+        assert_eq!(mouse_in_rect(&e, area), expected.is_some());
+        assert_eq!(mouse_position_in_rect(&e, area), expected);
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Action {
+        Down,
+        Word,
+        LineStart,
+        GoToLine,
+        Delete,
+        Yank,
+    }
+
+    fn vim_keymap() -> VimKeymap<Action> {
+        let k = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        VimKeymap::new()
+            .action(k('j'), Action::Down)
+            .action(k('w'), Action::Word)
+            .action(k('0'), Action::LineStart)
+            .action(
+                KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT),
+                Action::GoToLine,
+            )
+            .operator(k('d'), Action::Delete)
+            .operator(k('y'), Action::Yank)
+    }
+
+    fn feed(keymap: &mut VimKeymap<Action>, script: &str) -> Vec<KeymapResult<Action>> {
+        parse_keys(script)
+            .unwrap()
+            .into_iter()
+            .filter_map(|e| match e {
+                Event::Key(key) => Some(keymap.handle_key(&key)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn matched(
+        action: Action,
+        operator: Option<Action>,
+        count: Option<usize>,
+    ) -> KeymapResult<Action> {
+        KeymapResult::Matched(KeymapMatch {
+            action,
+            operator,
+            count,
+        })
+    }
+
+    #[rstest]
+    #[case("j", matched(Action::Down, None, None))]
+    #[case("5j", matched(Action::Down, None, Some(5)))]
+    #[case("10G", matched(Action::GoToLine, None, Some(10)))]
+    #[case("0", matched(Action::LineStart, None, None))]
+    #[case("dw", matched(Action::Word, Some(Action::Delete), None))]
+    #[case("d3w", matched(Action::Word, Some(Action::Delete), Some(3)))]
+    #[case("2d3w", matched(Action::Word, Some(Action::Delete), Some(6)))]
+    #[case("4dw", matched(Action::Word, Some(Action::Delete), Some(4)))]
+    #[case("dd", matched(Action::Delete, Some(Action::Delete), None))]
+    #[case("3yy", matched(Action::Yank, Some(Action::Yank), Some(3)))]
+    #[case("x", KeymapResult::Unmatched)]
+    #[case("dy", KeymapResult::Unmatched)]
+    #[case("5<C-j>", KeymapResult::Unmatched)]
+    fn test_vim_keymap(#[case] script: &str, #[case] expected: KeymapResult<Action>) {
+        let mut keymap = vim_keymap();
+        let results = feed(&mut keymap, script);
+        let (last, pending) = results.split_last().unwrap();
+        assert!(pending.iter().all(|r| *r == KeymapResult::Pending));
+        assert_eq!(*last, expected);
+        assert!(!keymap.is_pending());
+    }
+
+    #[test]
+    fn test_vim_keymap_pending_state() {
+        let mut keymap = vim_keymap();
+        feed(&mut keymap, "12");
+        assert_eq!(keymap.pending_count(), Some(12));
+        assert_eq!(keymap.pending_operator(), None);
+
+        feed(&mut keymap, "d");
+        assert_eq!(keymap.pending_count(), None);
+        assert_eq!(keymap.pending_operator(), Some(&Action::Delete));
+        assert!(keymap.is_pending());
+
+        keymap.reset();
+        assert!(!keymap.is_pending());
+    }
+
+    #[rstest]
+    #[case("5<Esc>")]
+    #[case("d<Esc>")]
+    #[case("3d2<Esc>")]
+    fn test_vim_keymap_escape(#[case] script: &str) {
+        let mut keymap = vim_keymap();
+        let results = feed(&mut keymap, script);
+        assert_eq!(results.last(), Some(&KeymapResult::Cancelled));
+        assert!(!keymap.is_pending());
+        assert_eq!(
+            feed(&mut keymap, "j"),
+            vec![matched(Action::Down, None, None)]
+        );
+    }
+
+    #[test]
+    fn test_vim_keymap_escape_not_pending() {
+        let mut keymap = vim_keymap();
+        assert_eq!(feed(&mut keymap, "<Esc>"), vec![KeymapResult::Unmatched]);
+    }
+
+    #[rstest]
+    #[case(KeyModifiers::SHIFT, KeyModifiers::NONE)]
+    #[case(KeyModifiers::NONE, KeyModifiers::SHIFT)]
+    #[case(KeyModifiers::SHIFT, KeyModifiers::SHIFT)]
+    #[case(KeyModifiers::NONE, KeyModifiers::NONE)]
+    fn test_vim_keymap_uppercase_shift(#[case] binding: KeyModifiers, #[case] event: KeyModifiers) {
+        let mut keymap = VimKeymap::new()
+            .action(KeyEvent::new(KeyCode::Char('G'), binding), Action::GoToLine)
+            .operator(KeyEvent::new(KeyCode::Char('D'), binding), Action::Delete);
+        assert_eq!(
+            keymap.handle_key(&KeyEvent::new(KeyCode::Char('G'), event)),
+            matched(Action::GoToLine, None, None)
+        );
+        assert_eq!(
+            keymap.handle_key(&KeyEvent::new(KeyCode::Char('D'), event)),
+            KeymapResult::Pending
+        );
+        assert_eq!(
+            keymap.handle_key(&KeyEvent::new(KeyCode::Char('D'), binding)),
+            matched(Action::Delete, Some(Action::Delete), None)
+        );
+    }
+
+    #[test]
+    fn test_vim_keymap_count_overflow() {
+        let mut keymap = vim_keymap();
+        let results = feed(&mut keymap, "99999999999999999999999999j");
+        assert_eq!(
+            results.last(),
+            Some(&matched(Action::Down, None, Some(usize::MAX)))
+        );
+
+        let results = feed(&mut keymap, "99999999999d99999999999w");
+        assert_eq!(
+            results.last(),
+            Some(&matched(
+                Action::Word,
+                Some(Action::Delete),
+                Some(usize::MAX)
+            ))
+        );
+
+        let mut keymap = vim_keymap().max_count(999);
+        let results = feed(&mut keymap, "12345j");
+        assert_eq!(
+            results.last(),
+            Some(&matched(Action::Down, None, Some(999)))
+        );
+        let results = feed(&mut keymap, "50d50w");
+        assert_eq!(
+            results.last(),
+            Some(&matched(Action::Word, Some(Action::Delete), Some(999)))
+        );
+    }
+
+    #[test]
+    fn test_vim_keymap_ignores_release() {
+        let mut keymap = vim_keymap();
+        let release = KeyEvent::new_with_kind(
+            KeyCode::Char('5'),
+            KeyModifiers::NONE,
+            KeyEventKind::Release,
+        );
+        assert_eq!(keymap.handle_key(&release), KeymapResult::Unmatched);
+        assert!(!keymap.is_pending());
+    }
+
+    #[test]
+    fn test_keymap_match_count_or() {
+        let m = KeymapMatch {
+            action: Action::Down,
+            operator: None,
+            count: None,
+        };
+        assert_eq!(m.count_or(1), 1);
+        let m = KeymapMatch {
+            count: Some(5),
+            ..m
+        };
+        assert_eq!(m.count_or(1), 5);
+    }
 }
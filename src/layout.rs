@@ -1,19 +1,1356 @@
-use ratatui_core::layout::{Constraint, Layout, Rect};
+use std::rc::Rc;
+
+use ratatui_core::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Margin, Offset, Position, Rect, Size},
+    style::Style,
+    widgets::StatefulWidget,
+};
+use ratatui_crossterm::crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
 
 pub fn calc_centered_area(base_area: Rect, area_width: u16, area_height: u16) -> Rect {
-    let vertical_pad = base_area.height.saturating_sub(area_height) / 2;
-    let vertical_layout = Layout::vertical(Constraint::from_lengths([
-        vertical_pad,
-        area_height,
-        vertical_pad,
-    ]))
-    .split(base_area);
-
-    let horizontal_pad = base_area.width.saturating_sub(area_width) / 2;
-    Layout::horizontal(Constraint::from_lengths([
-        horizontal_pad,
-        area_width,
-        horizontal_pad,
-    ]))
-    .split(vertical_layout[1])[1]
+    Placement::new(Extent::Length(area_width), Extent::Length(area_height)).calc(base_area)
+}
+
+pub trait RectExt {
+    fn outset(self, margin: Margin) -> Rect;
+    fn inset(self, margin: Margin) -> Rect;
+    fn split_top(self, height: u16) -> (Rect, Rect);
+    fn split_bottom(self, height: u16) -> (Rect, Rect);
+    fn split_left(self, width: u16) -> (Rect, Rect);
+    fn split_right(self, width: u16) -> (Rect, Rect);
+    fn center(self) -> Position;
+}
+
+impl RectExt for Rect {
+    fn outset(self, margin: Margin) -> Rect {
+        let doubled_margin_horizontal = margin.horizontal.saturating_mul(2);
+        let doubled_margin_vertical = margin.vertical.saturating_mul(2);
+        Rect {
+            x: self.x.saturating_sub(margin.horizontal),
+            y: self.y.saturating_sub(margin.vertical),
+            width: self.width.saturating_add(doubled_margin_horizontal),
+            height: self.height.saturating_add(doubled_margin_vertical),
+        }
+    }
+
+    fn inset(self, margin: Margin) -> Rect {
+        let horizontal = margin.horizontal.min(self.width / 2);
+        let vertical = margin.vertical.min(self.height / 2);
+        Rect {
+            x: self.x + horizontal,
+            y: self.y + vertical,
+            width: self.width - horizontal * 2,
+            height: self.height - vertical * 2,
+        }
+    }
+
+    fn split_top(self, height: u16) -> (Rect, Rect) {
+        let height = height.min(self.height);
+        (
+            Rect { height, ..self },
+            Rect {
+                y: self.y + height,
+                height: self.height - height,
+                ..self
+            },
+        )
+    }
+
+    fn split_bottom(self, height: u16) -> (Rect, Rect) {
+        self.split_top(self.height.saturating_sub(height))
+    }
+
+    fn split_left(self, width: u16) -> (Rect, Rect) {
+        let width = width.min(self.width);
+        (
+            Rect { width, ..self },
+            Rect {
+                x: self.x + width,
+                width: self.width - width,
+                ..self
+            },
+        )
+    }
+
+    fn split_right(self, width: u16) -> (Rect, Rect) {
+        self.split_left(self.width.saturating_sub(width))
+    }
+
+    fn center(self) -> Position {
+        Position::new(self.x + self.width / 2, self.y + self.height / 2)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    #[default]
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    pub const ALL: [Anchor; 9] = [
+        Anchor::TopLeft,
+        Anchor::Top,
+        Anchor::TopRight,
+        Anchor::Left,
+        Anchor::Center,
+        Anchor::Right,
+        Anchor::BottomLeft,
+        Anchor::Bottom,
+        Anchor::BottomRight,
+    ];
+
+    fn align(self) -> (Align, Align) {
+        match self {
+            Anchor::TopLeft => (Align::Start, Align::Start),
+            Anchor::Top => (Align::Center, Align::Start),
+            Anchor::TopRight => (Align::End, Align::Start),
+            Anchor::Left => (Align::Start, Align::Center),
+            Anchor::Center => (Align::Center, Align::Center),
+            Anchor::Right => (Align::End, Align::Center),
+            Anchor::BottomLeft => (Align::Start, Align::End),
+            Anchor::Bottom => (Align::Center, Align::End),
+            Anchor::BottomRight => (Align::End, Align::End),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Align {
+    Start,
+    Center,
+    End,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Extent {
+    Length(u16),
+    Percentage(u16),
+    Fill,
+}
+
+impl Extent {
+    fn resolve(self, available: u16) -> u16 {
+        match self {
+            Extent::Length(n) => n,
+            Extent::Percentage(p) => (available as u32 * p.min(100) as u32 / 100) as u16,
+            Extent::Fill => available,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Placement {
+    width: Extent,
+    height: Extent,
+    anchor: Anchor,
+    offset: Offset,
+    margin: Margin,
+    min_size: Size,
+    max_size: Size,
+}
+
+impl Placement {
+    pub fn new(width: Extent, height: Extent) -> Self {
+        Self {
+            width,
+            height,
+            anchor: Anchor::default(),
+            offset: Offset::default(),
+            margin: Margin::default(),
+            min_size: Size::ZERO,
+            max_size: Size::new(u16::MAX, u16::MAX),
+        }
+    }
+
+    pub fn anchor(mut self, anchor: Anchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    pub fn offset(mut self, offset: Offset) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn margin(mut self, margin: Margin) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    pub fn min_size(mut self, size: Size) -> Self {
+        self.min_size = size;
+        self
+    }
+
+    pub fn max_size(mut self, size: Size) -> Self {
+        self.max_size = size;
+        self
+    }
+
+    pub fn calc(&self, base_area: Rect) -> Rect {
+        let area = base_area.inset(self.margin);
+        let (h_align, v_align) = self.anchor.align();
+
+        let width = self.width.resolve(area.width);
+        let width = clamp_length(width, self.min_size.width, self.max_size.width);
+        let (x, width) = place(area.x, area.width, width, h_align, self.offset.x);
+
+        let height = self.height.resolve(area.height);
+        let height = clamp_length(height, self.min_size.height, self.max_size.height);
+        let (y, height) = place(area.y, area.height, height, v_align, self.offset.y);
+
+        Rect::new(x, y, width, height)
+    }
+}
+
+fn clamp_length(length: u16, min: u16, max: u16) -> u16 {
+    length.max(min).min(max)
+}
+
+fn place(start: u16, available: u16, length: u16, align: Align, offset: i32) -> (u16, u16) {
+    let length = length.min(available);
+    let free = available - length;
+    let pos = match align {
+        Align::Start => 0,
+        Align::Center => free / 2,
+        Align::End => free,
+    };
+    let pos = (pos as i32).saturating_add(offset).clamp(0, free as i32) as u16;
+    (start + pos, length)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    Below,
+    Above,
+    Right,
+    Left,
+}
+
+pub fn calc_popup_area(
+    base_area: Rect,
+    anchor: Rect,
+    popup_width: u16,
+    popup_height: u16,
+) -> (Rect, Side) {
+    calc_popup_area_with_sides(
+        base_area,
+        anchor,
+        popup_width,
+        popup_height,
+        &[Side::Below, Side::Above, Side::Right, Side::Left],
+    )
+}
+
+pub fn calc_popup_area_with_sides(
+    base_area: Rect,
+    anchor: Rect,
+    popup_width: u16,
+    popup_height: u16,
+    sides: &[Side],
+) -> (Rect, Side) {
+    let anchor = anchor.clamp(base_area);
+    let space = |side: Side| match side {
+        Side::Below => base_area.bottom().saturating_sub(anchor.bottom()),
+        Side::Above => anchor.y.saturating_sub(base_area.y),
+        Side::Right => base_area.right().saturating_sub(anchor.right()),
+        Side::Left => anchor.x.saturating_sub(base_area.x),
+    };
+    let required = |side: Side| match side {
+        Side::Below | Side::Above => popup_height,
+        Side::Right | Side::Left => popup_width,
+    };
+
+    let side = sides
+        .iter()
+        .copied()
+        .find(|&side| space(side) >= required(side))
+        .or_else(|| {
+            let fit = |side: Side| u32::from(space(side).min(required(side)));
+            sides.iter().copied().rev().max_by(|&a, &b| {
+                (fit(a) * u32::from(required(b))).cmp(&(fit(b) * u32::from(required(a))))
+            })
+        })
+        .unwrap_or(Side::Below);
+
+    let available = space(side);
+    let popup = match side {
+        Side::Below => {
+            let height = popup_height.min(available);
+            Rect::new(anchor.x, anchor.bottom(), popup_width, height)
+        }
+        Side::Above => {
+            let height = popup_height.min(available);
+            Rect::new(anchor.x, anchor.y - height, popup_width, height)
+        }
+        Side::Right => {
+            let width = popup_width.min(available);
+            Rect::new(anchor.right(), anchor.y, width, popup_height)
+        }
+        Side::Left => {
+            let width = popup_width.min(available);
+            Rect::new(anchor.x - width, anchor.y, width, popup_height)
+        }
+    };
+    (popup.clamp(base_area), side)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResponsiveLayout<K> {
+    breakpoints: Vec<Breakpoint<K>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Breakpoint<K> {
+    min_size: Size,
+    id: K,
+    layout: Layout,
+}
+
+impl<K> ResponsiveLayout<K> {
+    pub fn new(id: K, layout: Layout) -> Self {
+        Self {
+            breakpoints: vec![Breakpoint {
+                min_size: Size::ZERO,
+                id,
+                layout,
+            }],
+        }
+    }
+
+    pub fn breakpoint(self, min_width: u16, id: K, layout: Layout) -> Self {
+        self.breakpoint_size(Size::new(min_width, 0), id, layout)
+    }
+
+    pub fn breakpoint_size(mut self, min_size: Size, id: K, layout: Layout) -> Self {
+        self.breakpoints.push(Breakpoint {
+            min_size,
+            id,
+            layout,
+        });
+        self
+    }
+
+    pub fn select(&self, area: Rect) -> &K {
+        &self.select_breakpoint(area).id
+    }
+
+    pub fn split(&self, area: Rect) -> (&K, Rc<[Rect]>) {
+        let bp = self.select_breakpoint(area);
+        (&bp.id, bp.layout.split(area))
+    }
+
+    fn select_breakpoint(&self, area: Rect) -> &Breakpoint<K> {
+        self.breakpoints
+            .iter()
+            .filter(|bp| area.width >= bp.min_size.width && area.height >= bp.min_size.height)
+            .max_by_key(|bp| (bp.min_size.width, bp.min_size.height))
+            .unwrap_or(&self.breakpoints[0])
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SplitDirection {
+    #[default]
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SplitPaneState {
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_ratio"))]
+    ratio: f64,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    layout: SplitPaneLayout,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    dragging: bool,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+struct SplitPaneLayout {
+    area: Rect,
+    direction: SplitDirection,
+    first_min: u16,
+    second_min: u16,
+    first: Rect,
+    divider: Rect,
+    second: Rect,
+}
+
+impl Default for SplitPaneState {
+    fn default() -> Self {
+        Self::new(0.5)
+    }
+}
+
+impl SplitPaneState {
+    pub fn new(ratio: f64) -> Self {
+        Self {
+            ratio: clamp_ratio(ratio),
+            layout: SplitPaneLayout::default(),
+            dragging: false,
+        }
+    }
+
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    pub fn set_ratio(&mut self, ratio: f64) {
+        self.ratio = clamp_ratio(ratio);
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.dragging
+    }
+
+    pub fn areas(&self) -> [Rect; 2] {
+        [self.layout.first, self.layout.second]
+    }
+
+    pub fn divider_area(&self) -> Rect {
+        self.layout.divider
+    }
+
+    pub fn resize(&mut self, delta: i32) {
+        let l = self.layout;
+        let available = available_length(l.area, l.direction);
+        if available == 0 {
+            return;
+        }
+        let first = along(l.first, l.direction) as i32;
+        let first = first.saturating_add(delta).clamp(0, available as i32) as u16;
+        self.set_first_length(first);
+    }
+
+    pub fn handle_mouse_event(&mut self, e: &MouseEvent) -> bool {
+        match e.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.dragging = self.layout.divider.contains(Position::new(e.column, e.row));
+                self.dragging
+            }
+            MouseEventKind::Drag(MouseButton::Left) if self.dragging => {
+                let l = self.layout;
+                let (pos, start) = match l.direction {
+                    SplitDirection::Horizontal => (e.column, l.area.x),
+                    SplitDirection::Vertical => (e.row, l.area.y),
+                };
+                self.set_first_length(pos.saturating_sub(start));
+                true
+            }
+            MouseEventKind::Up(MouseButton::Left) if self.dragging => {
+                self.dragging = false;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn set_first_length(&mut self, first: u16) {
+        let l = self.layout;
+        let available = available_length(l.area, l.direction);
+        if available == 0 {
+            return;
+        }
+        let first = clamp_first(first, available, l.first_min, l.second_min);
+        self.ratio = first as f64 / available as f64;
+        self.layout = calc_split_layout(l.area, l.direction, self.ratio, l.first_min, l.second_min);
+    }
+}
+
+fn clamp_ratio(ratio: f64) -> f64 {
+    if ratio.is_nan() {
+        0.5
+    } else {
+        ratio.clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+fn deserialize_ratio<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let ratio = <Option<f64> as serde::Deserialize>::deserialize(deserializer)?;
+    Ok(clamp_ratio(ratio.unwrap_or(f64::NAN)))
+}
+
+#[derive(Debug, Clone)]
+pub struct SplitPane<'a> {
+    direction: SplitDirection,
+    first_min: u16,
+    second_min: u16,
+    divider_symbol: Option<&'a str>,
+    divider_style: Style,
+    dragging_style: Option<Style>,
+}
+
+impl<'a> SplitPane<'a> {
+    pub fn new(direction: SplitDirection) -> Self {
+        Self {
+            direction,
+            first_min: 0,
+            second_min: 0,
+            divider_symbol: None,
+            divider_style: Style::default(),
+            dragging_style: None,
+        }
+    }
+
+    pub fn horizontal() -> Self {
+        Self::new(SplitDirection::Horizontal)
+    }
+
+    pub fn vertical() -> Self {
+        Self::new(SplitDirection::Vertical)
+    }
+
+    pub fn min_sizes(mut self, first: u16, second: u16) -> Self {
+        self.first_min = first;
+        self.second_min = second;
+        self
+    }
+
+    pub fn divider_symbol(mut self, symbol: &'a str) -> Self {
+        self.divider_symbol = Some(symbol);
+        self
+    }
+
+    pub fn divider_style(mut self, style: Style) -> Self {
+        self.divider_style = style;
+        self
+    }
+
+    pub fn dragging_style(mut self, style: Style) -> Self {
+        self.dragging_style = Some(style);
+        self
+    }
+
+    pub fn split(&self, area: Rect, state: &SplitPaneState) -> [Rect; 2] {
+        let l = calc_split_layout(
+            area,
+            self.direction,
+            state.ratio,
+            self.first_min,
+            self.second_min,
+        );
+        [l.first, l.second]
+    }
+}
+
+impl StatefulWidget for SplitPane<'_> {
+    type State = SplitPaneState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        state.layout = calc_split_layout(
+            area,
+            self.direction,
+            state.ratio,
+            self.first_min,
+            self.second_min,
+        );
+
+        let symbol = self.divider_symbol.unwrap_or(match self.direction {
+            SplitDirection::Horizontal => "│",
+            SplitDirection::Vertical => "─",
+        });
+        let style = match self.dragging_style {
+            Some(style) if state.dragging => style,
+            _ => self.divider_style,
+        };
+        for p in state.layout.divider.positions() {
+            if let Some(cell) = buf.cell_mut(p) {
+                cell.set_symbol(symbol).set_style(style);
+            }
+        }
+    }
+}
+
+fn along(r: Rect, direction: SplitDirection) -> u16 {
+    match direction {
+        SplitDirection::Horizontal => r.width,
+        SplitDirection::Vertical => r.height,
+    }
+}
+
+fn available_length(area: Rect, direction: SplitDirection) -> u16 {
+    along(area, direction).saturating_sub(1)
+}
+
+fn clamp_first(first: u16, available: u16, first_min: u16, second_min: u16) -> u16 {
+    first
+        .min(available.saturating_sub(second_min))
+        .max(first_min.min(available))
+}
+
+fn calc_split_layout(
+    area: Rect,
+    direction: SplitDirection,
+    ratio: f64,
+    first_min: u16,
+    second_min: u16,
+) -> SplitPaneLayout {
+    let available = available_length(area, direction);
+    let first = (available as f64 * ratio).round() as u16;
+    let first = clamp_first(first, available, first_min, second_min);
+    let second = available - first;
+    let divider = along(area, direction).min(1);
+
+    let (first_area, divider_area, second_area) = match direction {
+        SplitDirection::Horizontal => (
+            Rect::new(area.x, area.y, first, area.height),
+            Rect::new(area.x + first, area.y, divider, area.height),
+            Rect::new(area.x + first + divider, area.y, second, area.height),
+        ),
+        SplitDirection::Vertical => (
+            Rect::new(area.x, area.y, area.width, first),
+            Rect::new(area.x, area.y + first, area.width, divider),
+            Rect::new(area.x, area.y + first + divider, area.width, second),
+        ),
+    };
+    SplitPaneLayout {
+        area,
+        direction,
+        first_min,
+        second_min,
+        first: first_area,
+        divider: divider_area,
+        second: second_area,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<K> {
+    rows: Vec<Constraint>,
+    columns: Vec<Constraint>,
+    row_gap: u16,
+    column_gap: u16,
+    cells: Vec<GridCell<K>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct GridCell<K> {
+    id: K,
+    row: usize,
+    column: usize,
+    row_span: usize,
+    column_span: usize,
+}
+
+impl<K> Grid<K> {
+    pub fn new<R, C>(rows: R, columns: C) -> Self
+    where
+        R: IntoIterator,
+        R::Item: Into<Constraint>,
+        C: IntoIterator,
+        C::Item: Into<Constraint>,
+    {
+        Self {
+            rows: rows.into_iter().map(Into::into).collect(),
+            columns: columns.into_iter().map(Into::into).collect(),
+            row_gap: 0,
+            column_gap: 0,
+            cells: Vec::new(),
+        }
+    }
+
+    pub fn gap(self, gap: u16) -> Self {
+        self.row_gap(gap).column_gap(gap)
+    }
+
+    pub fn row_gap(mut self, gap: u16) -> Self {
+        self.row_gap = gap;
+        self
+    }
+
+    pub fn column_gap(mut self, gap: u16) -> Self {
+        self.column_gap = gap;
+        self
+    }
+
+    pub fn cell(self, id: K, row: usize, column: usize) -> Self {
+        self.cell_span(id, row, column, 1, 1)
+    }
+
+    pub fn cell_span(
+        mut self,
+        id: K,
+        row: usize,
+        column: usize,
+        row_span: usize,
+        column_span: usize,
+    ) -> Self {
+        self.cells.push(GridCell {
+            id,
+            row,
+            column,
+            row_span: row_span.max(1),
+            column_span: column_span.max(1),
+        });
+        self
+    }
+
+    pub fn split(&self, area: Rect) -> Vec<(&K, Rect)> {
+        let rows = Layout::vertical(self.rows.iter().copied())
+            .spacing(self.row_gap)
+            .split(area);
+        let columns = Layout::horizontal(self.columns.iter().copied())
+            .spacing(self.column_gap)
+            .split(area);
+
+        self.cells
+            .iter()
+            .filter_map(|cell| {
+                let (top, bottom) = track_range(&rows, cell.row, cell.row_span)?;
+                let (left, right) = track_range(&columns, cell.column, cell.column_span)?;
+                let rect = Rect {
+                    x: left.x,
+                    y: top.y,
+                    width: right.right() - left.x,
+                    height: bottom.bottom() - top.y,
+                };
+                Some((&cell.id, rect))
+            })
+            .collect()
+    }
+}
+
+impl<K: PartialEq> Grid<K> {
+    pub fn cell_area(&self, area: Rect, id: &K) -> Option<Rect> {
+        self.split(area)
+            .into_iter()
+            .find(|(k, _)| *k == id)
+            .map(|(_, rect)| rect)
+    }
+}
+
+fn track_range(tracks: &[Rect], start: usize, span: usize) -> Option<(Rect, Rect)> {
+    let first = *tracks.get(start)?;
+    let end = start.saturating_add(span.max(1) - 1);
+    let last = tracks[end.min(tracks.len() - 1)];
+    Some((first, last))
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui_crossterm::crossterm::event::KeyModifiers;
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    #[case(Rect::new(0, 0, 10, 10), 5, 5, Rect::new(2, 2, 5, 5))]
+    #[case(Rect::new(0, 0, 11, 10), 4, 3, Rect::new(3, 3, 4, 3))]
+    #[case(Rect::new(3, 5, 7, 8), 2, 3, Rect::new(5, 7, 2, 3))]
+    #[case(Rect::new(0, 0, 3, 2), 2, 2, Rect::new(0, 0, 2, 2))]
+    #[case(Rect::new(0, 0, 80, 24), 31, 11, Rect::new(24, 6, 31, 11))]
+    #[case(Rect::new(2, 2, 5, 5), 10, 10, Rect::new(2, 2, 5, 5))]
+    #[case(Rect::new(0, 0, 0, 0), 10, 10, Rect::new(0, 0, 0, 0))]
+    fn test_calc_centered_area(
+        #[case] base: Rect,
+        #[case] width: u16,
+        #[case] height: u16,
+        #[case] expected: Rect,
+    ) {
+        assert_eq!(calc_centered_area(base, width, height), expected);
+    }
+
+    #[rstest]
+    #[case(Anchor::TopLeft, Rect::new(10, 20, 4, 2))]
+    #[case(Anchor::Top, Rect::new(13, 20, 4, 2))]
+    #[case(Anchor::TopRight, Rect::new(17, 20, 4, 2))]
+    #[case(Anchor::Left, Rect::new(10, 22, 4, 2))]
+    #[case(Anchor::Center, Rect::new(13, 22, 4, 2))]
+    #[case(Anchor::Right, Rect::new(17, 22, 4, 2))]
+    #[case(Anchor::BottomLeft, Rect::new(10, 25, 4, 2))]
+    #[case(Anchor::Bottom, Rect::new(13, 25, 4, 2))]
+    #[case(Anchor::BottomRight, Rect::new(17, 25, 4, 2))]
+    fn test_placement_anchor(#[case] anchor: Anchor, #[case] expected: Rect) {
+        let base = Rect::new(10, 20, 11, 7);
+        let actual = Placement::new(Extent::Length(4), Extent::Length(2))
+            .anchor(anchor)
+            .calc(base);
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case(Offset { x: 0, y: 0 }, Rect::new(3, 3, 4, 4))]
+    #[case(Offset { x: 2, y: -1 }, Rect::new(5, 2, 4, 4))]
+    #[case(Offset { x: 100, y: 100 }, Rect::new(6, 6, 4, 4))]
+    #[case(Offset { x: -100, y: -100 }, Rect::new(0, 0, 4, 4))]
+    #[case(Offset { x: i32::MAX, y: i32::MIN }, Rect::new(6, 0, 4, 4))]
+    fn test_placement_offset(#[case] offset: Offset, #[case] expected: Rect) {
+        let base = Rect::new(0, 0, 10, 10);
+        let actual = Placement::new(Extent::Length(4), Extent::Length(4))
+            .offset(offset)
+            .calc(base);
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case(
+        Extent::Percentage(50),
+        Extent::Percentage(50),
+        Rect::new(20, 6, 40, 12)
+    )]
+    #[case(
+        Extent::Percentage(33),
+        Extent::Percentage(100),
+        Rect::new(27, 0, 26, 24)
+    )]
+    #[case(
+        Extent::Percentage(200),
+        Extent::Percentage(0),
+        Rect::new(0, 12, 80, 0)
+    )]
+    #[case(Extent::Fill, Extent::Length(3), Rect::new(0, 10, 80, 3))]
+    fn test_placement_extent(
+        #[case] width: Extent,
+        #[case] height: Extent,
+        #[case] expected: Rect,
+    ) {
+        let base = Rect::new(0, 0, 80, 24);
+        assert_eq!(Placement::new(width, height).calc(base), expected);
+    }
+
+    #[rstest]
+    #[case(Size::new(0, 0), Size::new(100, 100), Rect::new(20, 6, 40, 12))]
+    #[case(Size::new(50, 15), Size::new(100, 100), Rect::new(15, 4, 50, 15))]
+    #[case(Size::new(0, 0), Size::new(30, 5), Rect::new(25, 9, 30, 5))]
+    #[case(Size::new(100, 100), Size::new(200, 200), Rect::new(0, 0, 80, 24))]
+    fn test_placement_min_max(#[case] min: Size, #[case] max: Size, #[case] expected: Rect) {
+        let base = Rect::new(0, 0, 80, 24);
+        let actual = Placement::new(Extent::Percentage(50), Extent::Percentage(50))
+            .min_size(min)
+            .max_size(max)
+            .calc(base);
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case(Margin::new(0, 0), Rect::new(0, 0, 20, 10))]
+    #[case(Margin::new(2, 1), Rect::new(2, 1, 16, 8))]
+    #[case(Margin::new(10, 5), Rect::new(10, 5, 0, 0))]
+    #[case(Margin::new(100, 100), Rect::new(10, 5, 0, 0))]
+    fn test_placement_margin(#[case] margin: Margin, #[case] expected: Rect) {
+        let base = Rect::new(0, 0, 20, 10);
+        let actual = Placement::new(Extent::Fill, Extent::Fill)
+            .margin(margin)
+            .calc(base);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_placement_always_contained() {
+        let offsets = [-3, -1, 0, 1, 3];
+        for base_w in 0..8 {
+            for base_h in 0..8 {
+                let base = Rect::new(5, 7, base_w, base_h);
+                for w in 0..10 {
+                    for h in 0..10 {
+                        for anchor in Anchor::ALL {
+                            for dx in offsets {
+                                for margin in 0..3 {
+                                    let actual =
+                                        Placement::new(Extent::Length(w), Extent::Length(h))
+                                            .anchor(anchor)
+                                            .offset(Offset { x: dx, y: -dx })
+                                            .margin(Margin::new(margin, margin))
+                                            .calc(base);
+                                    assert_eq!(
+                                        base.union(actual),
+                                        base,
+                                        "base={base:?} w={w} h={h} anchor={anchor:?} dx={dx} margin={margin} actual={actual:?}"
+                                    );
+                                    if margin == 0 {
+                                        assert_eq!(actual.width, w.min(base_w));
+                                        assert_eq!(actual.height, h.min(base_h));
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_placement_centered_odd_padding() {
+        for base_w in 0..20 {
+            for w in 0..=base_w {
+                let actual = calc_centered_area(Rect::new(0, 0, base_w, 1), w, 1);
+                let left = actual.x;
+                let right = base_w - actual.right();
+                assert_eq!(actual.width, w);
+                assert!(
+                    right == left || right == left + 1,
+                    "{base_w} {w} {actual:?}"
+                );
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(Rect::new(5, 3, 1, 1), (Rect::new(5, 4, 10, 5), Side::Below))]
+    #[case(Rect::new(5, 15, 1, 1), (Rect::new(5, 10, 10, 5), Side::Above))]
+    #[case(Rect::new(5, 18, 1, 1), (Rect::new(5, 13, 10, 5), Side::Above))]
+    #[case(Rect::new(35, 3, 1, 1), (Rect::new(30, 4, 10, 5), Side::Below))]
+    #[case(Rect::new(0, 0, 40, 1), (Rect::new(0, 1, 10, 5), Side::Below))]
+    #[case(Rect::new(10, 2, 20, 16), (Rect::new(30, 2, 10, 5), Side::Right))]
+    #[case(Rect::new(25, 2, 10, 16), (Rect::new(15, 2, 10, 5), Side::Left))]
+    #[case(Rect::new(0, 5, 40, 12), (Rect::new(0, 0, 10, 5), Side::Above))]
+    #[case(Rect::new(0, 3, 40, 12), (Rect::new(0, 15, 10, 5), Side::Below))]
+    #[case(Rect::new(0, 2, 40, 14), (Rect::new(0, 16, 10, 4), Side::Below))]
+    #[case(Rect::new(0, 4, 40, 14), (Rect::new(0, 0, 10, 4), Side::Above))]
+    #[case(Rect::new(50, 50, 1, 1), (Rect::new(30, 14, 10, 5), Side::Above))]
+    fn test_calc_popup_area(#[case] anchor: Rect, #[case] expected: (Rect, Side)) {
+        let base = Rect::new(0, 0, 40, 20);
+        assert_eq!(calc_popup_area(base, anchor, 10, 5), expected);
+    }
+
+    #[rstest]
+    #[case(&[Side::Right, Side::Below], (Rect::new(11, 5, 6, 3), Side::Right))]
+    #[case(&[Side::Left, Side::Right], (Rect::new(4, 5, 6, 3), Side::Left))]
+    #[case(&[Side::Above], (Rect::new(10, 2, 6, 3), Side::Above))]
+    #[case(&[], (Rect::new(10, 6, 6, 3), Side::Below))]
+    fn test_calc_popup_area_with_sides(#[case] sides: &[Side], #[case] expected: (Rect, Side)) {
+        let base = Rect::new(0, 0, 40, 20);
+        let anchor = Rect::new(10, 5, 1, 1);
+        assert_eq!(
+            calc_popup_area_with_sides(base, anchor, 6, 3, sides),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(Rect::new(60, 20, 5, 1), 70, 22, (Rect::new(10, 0, 70, 20), Side::Above))]
+    #[case(Rect::new(76, 22, 4, 1), 10, 30, (Rect::new(66, 0, 10, 24), Side::Left))]
+    #[case(Rect::new(79, 23, 1, 1), 90, 30, (Rect::new(0, 0, 79, 24), Side::Left))]
+    #[case(Rect::new(70, 12, 10, 12), 20, 20, (Rect::new(50, 4, 20, 20), Side::Left))]
+    fn test_calc_popup_area_bottom_right(
+        #[case] anchor: Rect,
+        #[case] width: u16,
+        #[case] height: u16,
+        #[case] expected: (Rect, Side),
+    ) {
+        let base = Rect::new(0, 0, 80, 24);
+        assert_eq!(calc_popup_area(base, anchor, width, height), expected);
+    }
+
+    #[test]
+    fn test_calc_popup_area_always_contained() {
+        let base = Rect::new(2, 3, 12, 9);
+        for x in 0..16 {
+            for y in 0..14 {
+                for (w, h) in [(1, 1), (3, 2), (12, 9), (20, 20)] {
+                    let anchor = Rect::new(x, y, 2, 1);
+                    let (actual, _) = calc_popup_area(base, anchor, w, h);
+                    assert_eq!(base.union(actual), base, "{anchor:?} {w} {h} {actual:?}");
+                    let clamped = anchor.clamp(base);
+                    assert!(
+                        actual.is_empty() || !actual.intersects(clamped),
+                        "{anchor:?} {w} {h} {actual:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    enum Panes {
+        Stacked,
+        Split,
+        Wide,
+        Tall,
+    }
+
+    fn responsive_layout() -> ResponsiveLayout<Panes> {
+        ResponsiveLayout::new(
+            Panes::Stacked,
+            Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]),
+        )
+        .breakpoint(
+            120,
+            Panes::Wide,
+            Layout::horizontal([
+                Constraint::Length(30),
+                Constraint::Fill(1),
+                Constraint::Length(30),
+            ]),
+        )
+        .breakpoint(
+            80,
+            Panes::Split,
+            Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)]),
+        )
+        .breakpoint_size(
+            Size::new(80, 60),
+            Panes::Tall,
+            Layout::vertical([Constraint::Length(10), Constraint::Fill(1)]),
+        )
+    }
+
+    #[rstest]
+    #[case(Rect::new(0, 0, 60, 20), Panes::Stacked, vec![Rect::new(0, 0, 60, 10), Rect::new(0, 10, 60, 10)])]
+    #[case(Rect::new(0, 0, 79, 80), Panes::Stacked, vec![Rect::new(0, 0, 79, 40), Rect::new(0, 40, 79, 40)])]
+    #[case(Rect::new(0, 0, 80, 20), Panes::Split, vec![Rect::new(0, 0, 24, 20), Rect::new(24, 0, 56, 20)])]
+    #[case(Rect::new(0, 0, 100, 60), Panes::Tall, vec![Rect::new(0, 0, 100, 10), Rect::new(0, 10, 100, 50)])]
+    #[case(Rect::new(0, 0, 120, 60), Panes::Wide, vec![Rect::new(0, 0, 30, 60), Rect::new(30, 0, 60, 60), Rect::new(90, 0, 30, 60)])]
+    fn test_responsive_layout(
+        #[case] area: Rect,
+        #[case] expected_id: Panes,
+        #[case] expected_areas: Vec<Rect>,
+    ) {
+        let layout = responsive_layout();
+        assert_eq!(layout.select(area), &expected_id);
+        let (id, areas) = layout.split(area);
+        assert_eq!(id, &expected_id);
+        assert_eq!(areas.to_vec(), expected_areas);
+    }
+
+    #[test]
+    fn test_responsive_layout_same_breakpoint_last_wins() {
+        let layout = ResponsiveLayout::new(Panes::Stacked, Layout::vertical([Constraint::Fill(1)]))
+            .breakpoint(80, Panes::Split, Layout::horizontal([Constraint::Fill(1)]))
+            .breakpoint(80, Panes::Wide, Layout::horizontal([Constraint::Fill(1)]));
+        assert_eq!(layout.select(Rect::new(0, 0, 80, 1)), &Panes::Wide);
+        assert_eq!(layout.select(Rect::new(0, 0, 0, 0)), &Panes::Stacked);
+    }
+
+    #[rstest]
+    #[case(SplitDirection::Horizontal, 0.5, (0, 0), [Rect::new(2, 1, 10, 5), Rect::new(13, 1, 10, 5)])]
+    #[case(SplitDirection::Horizontal, 0.3, (0, 0), [Rect::new(2, 1, 6, 5), Rect::new(9, 1, 14, 5)])]
+    #[case(SplitDirection::Horizontal, 0.0, (0, 0), [Rect::new(2, 1, 0, 5), Rect::new(3, 1, 20, 5)])]
+    #[case(SplitDirection::Horizontal, 1.0, (0, 0), [Rect::new(2, 1, 20, 5), Rect::new(23, 1, 0, 5)])]
+    #[case(SplitDirection::Horizontal, 0.1, (5, 5), [Rect::new(2, 1, 5, 5), Rect::new(8, 1, 15, 5)])]
+    #[case(SplitDirection::Horizontal, 0.9, (5, 5), [Rect::new(2, 1, 15, 5), Rect::new(18, 1, 5, 5)])]
+    #[case(SplitDirection::Horizontal, 0.5, (15, 15), [Rect::new(2, 1, 15, 5), Rect::new(18, 1, 5, 5)])]
+    #[case(SplitDirection::Vertical, 0.5, (0, 0), [Rect::new(2, 1, 21, 2), Rect::new(2, 4, 21, 2)])]
+    #[case(SplitDirection::Vertical, 0.0, (1, 3), [Rect::new(2, 1, 21, 1), Rect::new(2, 3, 21, 3)])]
+    fn test_split_pane_split(
+        #[case] direction: SplitDirection,
+        #[case] ratio: f64,
+        #[case] min_sizes: (u16, u16),
+        #[case] expected: [Rect; 2],
+    ) {
+        let area = Rect::new(2, 1, 21, 5);
+        let state = SplitPaneState::new(ratio);
+        let actual = SplitPane::new(direction)
+            .min_sizes(min_sizes.0, min_sizes.1)
+            .split(area, &state);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_split_pane_split_empty() {
+        let state = SplitPaneState::default();
+        let actual = SplitPane::horizontal()
+            .min_sizes(3, 3)
+            .split(Rect::new(1, 1, 0, 3), &state);
+        assert_eq!(actual, [Rect::new(1, 1, 0, 3), Rect::new(1, 1, 0, 3)]);
+    }
+
+    #[test]
+    fn test_split_pane_render() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 7, 2));
+        let mut state = SplitPaneState::new(0.5);
+        SplitPane::horizontal().render(buf.area, &mut buf, &mut state);
+        assert_eq!(buf, Buffer::with_lines(["   │   ", "   │   "]));
+        assert_eq!(
+            state.areas(),
+            [Rect::new(0, 0, 3, 2), Rect::new(4, 0, 3, 2)]
+        );
+        assert_eq!(state.divider_area(), Rect::new(3, 0, 1, 2));
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 3, 3));
+        SplitPane::vertical().render(buf.area, &mut buf, &mut state);
+        assert_eq!(buf, Buffer::with_lines(["   ", "───", "   "]));
+    }
+
+    #[test]
+    fn test_split_pane_nested() {
+        let area = Rect::new(0, 0, 21, 11);
+        let mut buf = Buffer::empty(area);
+        let mut outer = SplitPaneState::new(0.5);
+        let mut inner = SplitPaneState::new(0.5);
+        SplitPane::horizontal().render(area, &mut buf, &mut outer);
+        let [_, right] = outer.areas();
+        SplitPane::vertical().render(right, &mut buf, &mut inner);
+        assert_eq!(
+            inner.areas(),
+            [Rect::new(11, 0, 10, 5), Rect::new(11, 6, 10, 5)]
+        );
+    }
+
+    #[test]
+    fn test_split_pane_resize() {
+        let area = Rect::new(0, 0, 21, 5);
+        let mut buf = Buffer::empty(area);
+        let mut state = SplitPaneState::new(0.5);
+        let pane = SplitPane::horizontal().min_sizes(3, 4);
+        pane.clone().render(area, &mut buf, &mut state);
+
+        state.resize(2);
+        assert_eq!(state.areas()[0].width, 12);
+        assert_eq!(state.ratio(), 0.6);
+
+        state.resize(-100);
+        assert_eq!(state.areas()[0].width, 3);
+
+        state.resize(100);
+        assert_eq!(state.areas()[0].width, 16);
+        assert_eq!(state.areas()[1].width, 4);
+    }
+
+    #[test]
+    fn test_split_pane_resize_before_render() {
+        let mut state = SplitPaneState::new(0.5);
+        state.resize(3);
+        assert_eq!(state.ratio(), 0.5);
+    }
+
+    #[test]
+    fn test_split_pane_mouse_drag() {
+        let mouse = |kind, column, row| MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        };
+        let area = Rect::new(5, 0, 21, 5);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 30, 5));
+        let mut state = SplitPaneState::new(0.5);
+        SplitPane::horizontal()
+            .min_sizes(2, 2)
+            .render(area, &mut buf, &mut state);
+        assert_eq!(state.divider_area(), Rect::new(15, 0, 1, 5));
+
+        let down = MouseEventKind::Down(MouseButton::Left);
+        let drag = MouseEventKind::Drag(MouseButton::Left);
+        let up = MouseEventKind::Up(MouseButton::Left);
+
+        assert!(!state.handle_mouse_event(&mouse(down, 14, 2)));
+        assert!(!state.handle_mouse_event(&mouse(drag, 10, 2)));
+        assert_eq!(state.ratio(), 0.5);
+
+        assert!(state.handle_mouse_event(&mouse(down, 15, 2)));
+        assert!(state.is_dragging());
+        assert!(state.handle_mouse_event(&mouse(drag, 10, 2)));
+        assert_eq!(state.areas()[0], Rect::new(5, 0, 5, 5));
+        assert_eq!(state.ratio(), 0.25);
+
+        assert!(state.handle_mouse_event(&mouse(drag, 0, 2)));
+        assert_eq!(state.areas()[0], Rect::new(5, 0, 2, 5));
+        assert!(state.handle_mouse_event(&mouse(drag, 29, 2)));
+        assert_eq!(state.areas()[0], Rect::new(5, 0, 18, 5));
+
+        assert!(state.handle_mouse_event(&mouse(up, 29, 2)));
+        assert!(!state.is_dragging());
+        assert!(!state.handle_mouse_event(&mouse(drag, 10, 2)));
+        assert_eq!(state.areas()[0], Rect::new(5, 0, 18, 5));
+    }
+
+    #[test]
+    fn test_split_pane_state_ratio_clamp() {
+        assert_eq!(SplitPaneState::new(1.5).ratio(), 1.0);
+        assert_eq!(SplitPaneState::new(-0.5).ratio(), 0.0);
+        assert_eq!(SplitPaneState::new(f64::NAN).ratio(), 0.5);
+    }
+
+    #[test]
+    fn test_split_pane_mouse_drag_zero_length() {
+        let mouse = |kind, column, row| MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        };
+        let area = Rect::new(0, 0, 1, 3);
+        let mut buf = Buffer::empty(area);
+        let mut state = SplitPaneState::new(0.3);
+        SplitPane::horizontal().render(area, &mut buf, &mut state);
+
+        let column = state.divider_area().x;
+        state.handle_mouse_event(&mouse(MouseEventKind::Down(MouseButton::Left), column, 1));
+        state.handle_mouse_event(&mouse(MouseEventKind::Drag(MouseButton::Left), 0, 1));
+        state.resize(1);
+        assert_eq!(state.ratio(), 0.3);
+    }
+
+    #[cfg(feature = "serde")]
+    #[rstest]
+    #[case(0.25)]
+    #[case(1.0)]
+    fn test_split_pane_state_serde_round_trip(#[case] ratio: f64) {
+        let state = SplitPaneState::new(ratio);
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(json, format!("{{\"ratio\":{ratio:?}}}"));
+        let restored: SplitPaneState = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, state);
+    }
+
+    #[cfg(feature = "serde")]
+    #[rstest]
+    #[case(r#"{"ratio":1.5}"#, 1.0)]
+    #[case(r#"{"ratio":-2.0}"#, 0.0)]
+    #[case(r#"{"ratio":null}"#, 0.5)]
+    fn test_split_pane_state_deserialize_clamps(#[case] json: &str, #[case] expected: f64) {
+        let state: SplitPaneState = serde_json::from_str(json).unwrap();
+        assert_eq!(state.ratio(), expected);
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    enum Cell {
+        Header,
+        Cpu,
+        Memory,
+        Disk,
+        Log,
+        Outside,
+    }
+
+    fn dashboard() -> Grid<Cell> {
+        Grid::new(
+            [
+                Constraint::Length(3),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ],
+            [Constraint::Percentage(50), Constraint::Percentage(50)],
+        )
+        .cell_span(Cell::Header, 0, 0, 1, 2)
+        .cell(Cell::Cpu, 1, 0)
+        .cell(Cell::Memory, 1, 1)
+        .cell(Cell::Disk, 2, 0)
+        .cell_span(Cell::Log, 1, 1, 5, 5)
+        .cell(Cell::Outside, 3, 0)
+    }
+
+    #[test]
+    fn test_grid_split() {
+        let area = Rect::new(0, 0, 40, 23);
+        let grid = dashboard();
+        let actual = grid.split(area);
+        let expected = vec![
+            (&Cell::Header, Rect::new(0, 0, 40, 3)),
+            (&Cell::Cpu, Rect::new(0, 3, 20, 10)),
+            (&Cell::Memory, Rect::new(20, 3, 20, 10)),
+            (&Cell::Disk, Rect::new(0, 13, 20, 10)),
+            (&Cell::Log, Rect::new(20, 3, 20, 20)),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_grid_split_with_gap() {
+        let area = Rect::new(1, 1, 41, 25);
+        let grid = dashboard().row_gap(1).column_gap(1);
+        let actual = grid.split(area);
+        let expected = vec![
+            (&Cell::Header, Rect::new(1, 1, 41, 3)),
+            (&Cell::Cpu, Rect::new(1, 5, 20, 10)),
+            (&Cell::Memory, Rect::new(22, 5, 20, 10)),
+            (&Cell::Disk, Rect::new(1, 16, 20, 10)),
+            (&Cell::Log, Rect::new(22, 5, 20, 21)),
+        ];
+        assert_eq!(actual, expected);
+        assert_eq!(grid, dashboard().gap(1));
+    }
+
+    #[test]
+    fn test_grid_cell_area() {
+        let area = Rect::new(0, 0, 40, 23);
+        let grid = dashboard();
+        assert_eq!(
+            grid.cell_area(area, &Cell::Memory),
+            Some(Rect::new(20, 3, 20, 10))
+        );
+        assert_eq!(grid.cell_area(area, &Cell::Outside), None);
+    }
+
+    #[test]
+    fn test_grid_cell_span_extremes() {
+        let grid = Grid::new(
+            [Constraint::Length(2), Constraint::Length(3)],
+            [Constraint::Length(4), Constraint::Length(5)],
+        )
+        .cell_span(Cell::Cpu, 0, 0, usize::MAX, usize::MAX)
+        .cell_span(Cell::Memory, 1, 1, 0, 0);
+        let expected = vec![
+            (&Cell::Cpu, Rect::new(0, 0, 9, 5)),
+            (&Cell::Memory, Rect::new(4, 2, 5, 3)),
+        ];
+        assert_eq!(grid.split(Rect::new(0, 0, 20, 10)), expected);
+    }
+
+    #[test]
+    fn test_grid_empty() {
+        let grid: Grid<Cell> =
+            Grid::new([] as [Constraint; 0], [Constraint::Fill(1)]).cell(Cell::Cpu, 0, 0);
+        assert_eq!(grid.split(Rect::new(0, 0, 10, 10)), vec![]);
+    }
+
+    #[rstest]
+    #[case(Rect::new(5, 5, 10, 4), Margin::new(2, 1), Rect::new(3, 4, 14, 6))]
+    #[case(Rect::new(1, 0, 10, 4), Margin::new(2, 1), Rect::new(0, 0, 14, 6))]
+    #[case(Rect::new(0, 0, 10, 1), Margin::new(u16::MAX, 0), Rect { x: 0, y: 0, width: u16::MAX, height: 1 })]
+    fn test_rect_outset(#[case] rect: Rect, #[case] margin: Margin, #[case] expected: Rect) {
+        assert_eq!(rect.outset(margin), expected);
+    }
+
+    #[rstest]
+    #[case(Rect::new(5, 5, 10, 4), Margin::new(2, 1), Rect::new(7, 6, 6, 2))]
+    #[case(Rect::new(5, 5, 10, 4), Margin::new(5, 2), Rect::new(10, 7, 0, 0))]
+    #[case(Rect::new(5, 5, 11, 5), Margin::new(9, 9), Rect::new(10, 7, 1, 1))]
+    fn test_rect_inset(#[case] rect: Rect, #[case] margin: Margin, #[case] expected: Rect) {
+        assert_eq!(rect.inset(margin), expected);
+    }
+
+    #[rstest]
+    #[case(3, (Rect::new(2, 1, 10, 3), Rect::new(2, 4, 10, 5)), (Rect::new(2, 1, 10, 5), Rect::new(2, 6, 10, 3)))]
+    #[case(0, (Rect::new(2, 1, 10, 0), Rect::new(2, 1, 10, 8)), (Rect::new(2, 1, 10, 8), Rect::new(2, 9, 10, 0)))]
+    #[case(20, (Rect::new(2, 1, 10, 8), Rect::new(2, 9, 10, 0)), (Rect::new(2, 1, 10, 0), Rect::new(2, 1, 10, 8)))]
+    fn test_rect_split_vertical(
+        #[case] n: u16,
+        #[case] expected_top: (Rect, Rect),
+        #[case] expected_bottom: (Rect, Rect),
+    ) {
+        let rect = Rect::new(2, 1, 10, 8);
+        assert_eq!(rect.split_top(n), expected_top);
+        assert_eq!(rect.split_bottom(n), expected_bottom);
+    }
+
+    #[rstest]
+    #[case(3, (Rect::new(2, 1, 3, 8), Rect::new(5, 1, 7, 8)), (Rect::new(2, 1, 7, 8), Rect::new(9, 1, 3, 8)))]
+    #[case(20, (Rect::new(2, 1, 10, 8), Rect::new(12, 1, 0, 8)), (Rect::new(2, 1, 0, 8), Rect::new(2, 1, 10, 8)))]
+    fn test_rect_split_horizontal(
+        #[case] n: u16,
+        #[case] expected_left: (Rect, Rect),
+        #[case] expected_right: (Rect, Rect),
+    ) {
+        let rect = Rect::new(2, 1, 10, 8);
+        assert_eq!(rect.split_left(n), expected_left);
+        assert_eq!(rect.split_right(n), expected_right);
+    }
+
+    #[rstest]
+    #[case(Rect::new(2, 3, 10, 8), Position::new(7, 7))]
+    #[case(Rect::new(2, 3, 11, 9), Position::new(7, 7))]
+    #[case(Rect::new(2, 3, 0, 0), Position::new(2, 3))]
+    fn test_rect_center(#[case] rect: Rect, #[case] expected: Position) {
+        assert_eq!(rect.center(), expected);
+    }
 }
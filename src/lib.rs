@@ -1,5 +1,15 @@
+pub mod columns;
 pub mod dialog;
+pub mod focus;
+pub mod format;
 pub mod highlight;
+pub mod hint;
 pub mod keys;
 pub mod layout;
 pub mod spans;
+pub mod status;
+#[cfg(feature = "syntax")]
+pub mod syntax;
+pub mod tabs;
+pub mod text;
+pub mod wrap;
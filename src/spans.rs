@@ -1,6 +1,10 @@
+use std::{borrow::Cow, ops::Range};
+
 use ratatui_core::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
     style::{Color, Modifier, Style},
-    text::Span,
+    text::{Line, Span},
 };
 
 pub fn truncate_spans(spans: Vec<Span<'_>>, max_width: usize) -> TruncateSpans<'_> {
@@ -11,12 +15,21 @@ pub fn truncate_spans(spans: Vec<Span<'_>>, max_width: usize) -> TruncateSpans<'
     }
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TruncatePosition {
+    #[default]
+    End,
+    Start,
+    Middle,
+}
+
 #[derive(Default)]
 pub struct TruncateSpans<'a> {
     spans: Vec<Span<'a>>,
     max_width: usize,
     ellipsis: &'a str,
     ellipsis_style: Style,
+    position: TruncatePosition,
 }
 
 impl<'a> TruncateSpans<'a> {
@@ -45,7 +58,19 @@ impl<'a> TruncateSpans<'a> {
         self
     }
 
+    pub fn position(mut self, position: TruncatePosition) -> Self {
+        self.position = position;
+        self
+    }
+
     pub fn into_spans(self) -> Vec<Span<'a>> {
+        match self.position {
+            TruncatePosition::End => self.truncate_end(),
+            TruncatePosition::Start | TruncatePosition::Middle => self.truncate_start_or_middle(),
+        }
+    }
+
+    fn truncate_end(self) -> Vec<Span<'a>> {
         let total_spans = self.spans.len();
         let span_widths: Vec<usize> = self
             .spans
@@ -95,10 +120,827 @@ impl<'a> TruncateSpans<'a> {
 
         ret
     }
+
+    fn truncate_start_or_middle(self) -> Vec<Span<'a>> {
+        let total_width: usize = self
+            .spans
+            .iter()
+            .map(|s| console::measure_text_width(&s.content))
+            .sum();
+        if total_width <= self.max_width {
+            return self.spans;
+        }
+
+        let ellipsis_width = console::measure_text_width(self.ellipsis);
+        if ellipsis_width >= self.max_width {
+            let truncated_ellipsis = console::truncate_str(self.ellipsis, self.max_width, "");
+            return vec![Span::from(truncated_ellipsis).style(self.ellipsis_style)];
+        }
+
+        let rest_w = self.max_width - ellipsis_width;
+        let mut ret = Vec::new();
+        let tail_w = if self.position == TruncatePosition::Middle {
+            ret = take_head(self.spans.clone(), rest_w.div_ceil(2));
+            rest_w - spans_width(&ret)
+        } else {
+            rest_w
+        };
+
+        if !self.ellipsis.is_empty() {
+            ret.push(Span::from(self.ellipsis).style(self.ellipsis_style));
+        }
+        ret.extend(take_tail(self.spans, tail_w));
+        ret
+    }
+}
+
+pub fn pad_spans(spans: Vec<Span<'_>>, width: usize) -> AlignSpans<'_> {
+    align_spans(spans, width, Alignment::Left)
+}
+
+pub fn align_spans(spans: Vec<Span<'_>>, width: usize, alignment: Alignment) -> AlignSpans<'_> {
+    AlignSpans {
+        spans,
+        width,
+        alignment,
+        fill: ' ',
+        fill_style: Style::default(),
+        ellipsis: "",
+        ellipsis_style: Style::default(),
+        truncate: TruncatePosition::End,
+    }
+}
+
+pub struct AlignSpans<'a> {
+    spans: Vec<Span<'a>>,
+    width: usize,
+    alignment: Alignment,
+    fill: char,
+    fill_style: Style,
+    ellipsis: &'a str,
+    ellipsis_style: Style,
+    truncate: TruncatePosition,
+}
+
+impl<'a> AlignSpans<'a> {
+    pub fn fill(mut self, fill: char) -> Self {
+        self.fill = if !fill.is_control()
+            && console::measure_text_width(fill.encode_utf8(&mut [0; 4])) == 1
+        {
+            fill
+        } else {
+            ' '
+        };
+        self
+    }
+
+    pub fn fill_style(mut self, style: Style) -> Self {
+        self.fill_style = style;
+        self
+    }
+
+    pub fn fill_fg(mut self, color: Color) -> Self {
+        self.fill_style = self.fill_style.fg(color);
+        self
+    }
+
+    pub fn fill_bg(mut self, color: Color) -> Self {
+        self.fill_style = self.fill_style.bg(color);
+        self
+    }
+
+    pub fn fill_modifier(mut self, modifier: Modifier) -> Self {
+        self.fill_style = self.fill_style.add_modifier(modifier);
+        self
+    }
+
+    pub fn ellipsis(mut self, ellipsis: &'a str) -> Self {
+        self.ellipsis = ellipsis;
+        self
+    }
+
+    pub fn ellipsis_style(mut self, style: Style) -> Self {
+        self.ellipsis_style = style;
+        self
+    }
+
+    pub fn ellipsis_fg(mut self, color: Color) -> Self {
+        self.ellipsis_style = self.ellipsis_style.fg(color);
+        self
+    }
+
+    pub fn ellipsis_bg(mut self, color: Color) -> Self {
+        self.ellipsis_style = self.ellipsis_style.bg(color);
+        self
+    }
+
+    pub fn ellipsis_modifier(mut self, modifier: Modifier) -> Self {
+        self.ellipsis_style = self.ellipsis_style.add_modifier(modifier);
+        self
+    }
+
+    pub fn truncate(mut self, truncate: TruncatePosition) -> Self {
+        self.truncate = truncate;
+        self
+    }
+
+    pub fn into_spans(self) -> Vec<Span<'a>> {
+        let spans: Vec<Span<'a>> = truncate_spans(self.spans, self.width)
+            .ellipsis(self.ellipsis)
+            .ellipsis_style(self.ellipsis_style)
+            .position(self.truncate)
+            .into_spans()
+            .into_iter()
+            .filter(|s| !s.content.is_empty())
+            .collect();
+
+        let pad = self.width.saturating_sub(spans_width(&spans));
+        let (left, right) = match self.alignment {
+            Alignment::Left => (0, pad),
+            Alignment::Center => (pad / 2, pad - pad / 2),
+            Alignment::Right => (pad, 0),
+        };
+
+        let mut ret = Vec::with_capacity(spans.len() + 2);
+        if left > 0 {
+            ret.push(fill_span(self.fill, self.fill_style, left));
+        }
+        ret.extend(spans);
+        if right > 0 {
+            ret.push(fill_span(self.fill, self.fill_style, right));
+        }
+        ret
+    }
+}
+
+fn fill_span<'a>(fill: char, style: Style, width: usize) -> Span<'a> {
+    Span::styled(fill.to_string().repeat(width), style)
+}
+
+pub fn sanitize_spans(spans: Vec<Span<'_>>) -> SanitizeSpans<'_> {
+    SanitizeSpans {
+        spans,
+        tab_width: 8,
+        start_column: 0,
+        control_style: Style::default(),
+    }
+}
+
+pub struct SanitizeSpans<'a> {
+    spans: Vec<Span<'a>>,
+    tab_width: usize,
+    start_column: usize,
+    control_style: Style,
+}
+
+enum SanitizedPiece {
+    Text(usize, usize),
+    Tab(usize),
+    Control(String),
+}
+
+impl<'a> SanitizeSpans<'a> {
+    pub fn tab_width(mut self, tab_width: usize) -> Self {
+        self.tab_width = tab_width;
+        self
+    }
+
+    pub fn start_column(mut self, column: usize) -> Self {
+        self.start_column = column;
+        self
+    }
+
+    pub fn control_style(mut self, style: Style) -> Self {
+        self.control_style = style;
+        self
+    }
+
+    pub fn control_fg(mut self, color: Color) -> Self {
+        self.control_style = self.control_style.fg(color);
+        self
+    }
+
+    pub fn control_bg(mut self, color: Color) -> Self {
+        self.control_style = self.control_style.bg(color);
+        self
+    }
+
+    pub fn control_modifier(mut self, modifier: Modifier) -> Self {
+        self.control_style = self.control_style.add_modifier(modifier);
+        self
+    }
+
+    pub fn into_spans(mut self) -> Vec<Span<'a>> {
+        let mut column = self.start_column;
+        let spans = std::mem::take(&mut self.spans);
+        let ansi = ansi_ranges(&spans);
+        let mut ret = Vec::with_capacity(spans.len());
+        let mut offset = 0;
+        for span in spans {
+            let len = span.content.len();
+            let first = ansi.partition_point(|r| r.end <= offset);
+            let ansi_in_span: Vec<Range<usize>> = ansi[first..]
+                .iter()
+                .take_while(|r| r.start < offset + len)
+                .map(|r| r.start.saturating_sub(offset)..(r.end - offset).min(len))
+                .collect();
+            offset += len;
+
+            let pieces = self.sanitize(&span.content, &ansi_in_span, &mut column);
+            if let [SanitizedPiece::Text(0, end)] = pieces[..] {
+                if end == span.content.len() {
+                    ret.push(span);
+                    continue;
+                }
+            }
+            for piece in pieces {
+                let (content, style) = match piece {
+                    SanitizedPiece::Text(start, end) => {
+                        let content = match &span.content {
+                            Cow::Borrowed(c) => Cow::Borrowed(&c[start..end]),
+                            Cow::Owned(c) => Cow::Owned(c[start..end].to_string()),
+                        };
+                        (content, span.style)
+                    }
+                    SanitizedPiece::Tab(n) => (Cow::Owned(" ".repeat(n)), span.style),
+                    SanitizedPiece::Control(s) => {
+                        (Cow::Owned(s), span.style.patch(self.control_style))
+                    }
+                };
+                ret.push(Span::styled(content, style));
+            }
+        }
+        ret
+    }
+
+    fn sanitize(&self, s: &str, ansi: &[Range<usize>], column: &mut usize) -> Vec<SanitizedPiece> {
+        let mut parts = Vec::new();
+        let mut pos = 0;
+        for r in ansi {
+            if pos < r.start {
+                parts.push(pos..r.start);
+            }
+            pos = r.end;
+        }
+        if pos < s.len() {
+            parts.push(pos..s.len());
+        }
+
+        let mut pieces = Vec::new();
+        for Range { start: offset, end } in parts {
+            let part = &s[offset..end];
+            let mut run_start = offset;
+            for (i, c) in part.char_indices() {
+                if !matches!(c, '\x00'..='\x1f' | '\x7f' | '\u{80}'..='\u{9f}') {
+                    continue;
+                }
+                let start = offset + i;
+                if run_start < start {
+                    *column += console::measure_text_width(&s[run_start..start]);
+                    pieces.push(SanitizedPiece::Text(run_start, start));
+                }
+                run_start = start + c.len_utf8();
+
+                match c {
+                    '\t' if self.tab_width > 0 => {
+                        let n = self.tab_width - *column % self.tab_width;
+                        *column += n;
+                        pieces.push(SanitizedPiece::Tab(n));
+                    }
+                    '\t' => {}
+                    '\u{80}'..='\u{9f}' => {
+                        let p = format!("<{:02x}>", c as u32);
+                        *column += p.len();
+                        pieces.push(SanitizedPiece::Control(p));
+                    }
+                    _ => {
+                        *column += 2;
+                        let p = format!("^{}", ((c as u8) ^ 0x40) as char);
+                        pieces.push(SanitizedPiece::Control(p));
+                    }
+                }
+            }
+            if run_start < end {
+                *column += console::measure_text_width(&s[run_start..end]);
+                pieces.push(SanitizedPiece::Text(run_start, end));
+            }
+        }
+        pieces
+    }
+}
+
+fn ansi_ranges(spans: &[Span<'_>]) -> Vec<Range<usize>> {
+    if !spans.iter().any(|s| s.content.contains('\x1b')) {
+        return Vec::new();
+    }
+    let text: String = spans.iter().map(|s| s.content.as_ref()).collect();
+    let mut ranges = Vec::new();
+    let mut offset = 0;
+    for (part, is_ansi) in console::AnsiCodeIterator::new(&text) {
+        if is_ansi {
+            ranges.push(offset..offset + part.len());
+        }
+        offset += part.len();
+    }
+    ranges
+}
+
+pub fn normalize_spans(spans: Vec<Span<'_>>) -> NormalizeSpans<'_> {
+    NormalizeSpans {
+        spans,
+        source: None,
+    }
+}
+
+pub struct NormalizeSpans<'a> {
+    spans: Vec<Span<'a>>,
+    source: Option<&'a str>,
+}
+
+impl<'a> NormalizeSpans<'a> {
+    pub fn source(mut self, source: &'a str) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    pub fn into_spans(self) -> Vec<Span<'a>> {
+        let mut ret: Vec<(Span<'a>, Option<Range<usize>>)> = Vec::with_capacity(self.spans.len());
+        let mut offset = 0;
+        for span in self.spans {
+            if span.content.is_empty() {
+                continue;
+            }
+            let range = offset..offset + span.content.len();
+            offset = range.end;
+            let (span, range) = match self.source {
+                Some(source) if source.get(range.clone()) == Some(&*span.content) => (
+                    Span::styled(&source[range.clone()], span.style),
+                    Some(range),
+                ),
+                _ => (span, None),
+            };
+
+            match ret.last_mut() {
+                Some((last, last_range)) if last.style == span.style => {
+                    match (self.source, last_range.as_mut(), range) {
+                        (Some(source), Some(last_range), Some(range))
+                            if last_range.end == range.start =>
+                        {
+                            last_range.end = range.end;
+                            last.content = Cow::Borrowed(&source[last_range.clone()]);
+                        }
+                        _ => {
+                            last.content.to_mut().push_str(&span.content);
+                            *last_range = None;
+                        }
+                    }
+                }
+                _ => ret.push((span, range)),
+            }
+        }
+        ret.into_iter().map(|(span, _)| span).collect()
+    }
+}
+
+pub(crate) fn spans_width(spans: &[Span<'_>]) -> usize {
+    spans
+        .iter()
+        .map(|s| console::measure_text_width(&s.content))
+        .sum()
+}
+
+pub(crate) fn slice_spans<'a>(spans: &[Span<'a>], start: usize, end: usize) -> Vec<Span<'a>> {
+    let mut ret = Vec::new();
+    let mut offset = 0;
+    for span in spans {
+        let len = span.content.len();
+        let s = start.max(offset).min(offset + len) - offset;
+        let e = end.max(offset).min(offset + len) - offset;
+        offset += len;
+        if s >= e {
+            continue;
+        }
+        let content = match &span.content {
+            Cow::Borrowed(c) => Cow::Borrowed(&c[s..e]),
+            Cow::Owned(c) => Cow::Owned(c[s..e].to_string()),
+        };
+        ret.push(Span::styled(content, span.style));
+    }
+    ret
+}
+
+pub(crate) fn take_head(spans: Vec<Span<'_>>, width: usize) -> Vec<Span<'_>> {
+    let mut rest_w = width;
+    let mut ret = Vec::new();
+    for span in spans {
+        if rest_w == 0 {
+            break;
+        }
+        let w = console::measure_text_width(&span.content);
+        if w <= rest_w {
+            rest_w -= w;
+            ret.push(span);
+        } else {
+            let truncated = console::truncate_str(&span.content, rest_w, "").to_string();
+            if !truncated.is_empty() {
+                ret.push(Span::styled(truncated, span.style));
+            }
+            break;
+        }
+    }
+    ret
+}
+
+fn take_tail(spans: Vec<Span<'_>>, width: usize) -> Vec<Span<'_>> {
+    let mut rest_w = width;
+    let mut ret = Vec::new();
+    for span in spans.into_iter().rev() {
+        if rest_w == 0 {
+            break;
+        }
+        let w = console::measure_text_width(&span.content);
+        if w <= rest_w {
+            rest_w -= w;
+            ret.push(span);
+        } else {
+            let mut start = span.content.len();
+            for (i, c) in span.content.char_indices().rev() {
+                let cw = console::measure_text_width(&span.content[i..i + c.len_utf8()]);
+                if cw > rest_w {
+                    break;
+                }
+                rest_w -= cw;
+                start = i;
+            }
+            if start < span.content.len() {
+                ret.push(Span::styled(span.content[start..].to_string(), span.style));
+            }
+            break;
+        }
+    }
+    ret.reverse();
+    ret
+}
+
+pub fn ansi_to_spans(s: &str) -> Vec<Span<'_>> {
+    let mut style = Style::default();
+    let mut ret = Vec::new();
+    for (text, is_ansi) in console::AnsiCodeIterator::new(s) {
+        if is_ansi {
+            apply_ansi_code(&mut style, text);
+        } else if !text.is_empty() {
+            ret.push(Span::styled(text, style));
+        }
+    }
+    ret
+}
+
+pub fn ansi_to_lines(s: &str) -> Vec<Line<'_>> {
+    let mut style = Style::default();
+    let mut lines = Vec::new();
+    let mut spans = Vec::new();
+    for (text, is_ansi) in console::AnsiCodeIterator::new(s) {
+        if is_ansi {
+            apply_ansi_code(&mut style, text);
+            continue;
+        }
+        let mut parts = text.split('\n');
+        if let Some(first) = parts.next() {
+            push_text(&mut spans, first, style);
+        }
+        for part in parts {
+            lines.push(Line::from(std::mem::take(&mut spans)));
+            push_text(&mut spans, part, style);
+        }
+    }
+    if !spans.is_empty() || lines.is_empty() {
+        lines.push(Line::from(spans));
+    }
+    lines
+}
+
+fn push_text<'a>(spans: &mut Vec<Span<'a>>, text: &'a str, style: Style) {
+    let text = text.strip_suffix('\r').unwrap_or(text);
+    if !text.is_empty() {
+        spans.push(Span::styled(text, style));
+    }
+}
+
+fn apply_ansi_code(style: &mut Style, code: &str) {
+    let Some(params) = code
+        .strip_prefix("\x1b[")
+        .and_then(|code| code.strip_suffix('m'))
+    else {
+        return;
+    };
+    let params: Vec<Option<u8>> = params
+        .split(';')
+        .map(|p| {
+            if p.is_empty() {
+                Some(0)
+            } else {
+                p.parse().ok()
+            }
+        })
+        .collect();
+
+    let mut iter = params.into_iter();
+    while let Some(param) = iter.next() {
+        let Some(param) = param else {
+            continue;
+        };
+        match param {
+            0 => *style = Style::default(),
+            1 => add_modifier(style, Modifier::BOLD),
+            2 => add_modifier(style, Modifier::DIM),
+            3 => add_modifier(style, Modifier::ITALIC),
+            4 => add_modifier(style, Modifier::UNDERLINED),
+            5 => add_modifier(style, Modifier::SLOW_BLINK),
+            6 => add_modifier(style, Modifier::RAPID_BLINK),
+            7 => add_modifier(style, Modifier::REVERSED),
+            8 => add_modifier(style, Modifier::HIDDEN),
+            9 => add_modifier(style, Modifier::CROSSED_OUT),
+            22 => remove_modifier(style, Modifier::BOLD | Modifier::DIM),
+            23 => remove_modifier(style, Modifier::ITALIC),
+            24 => remove_modifier(style, Modifier::UNDERLINED),
+            25 => remove_modifier(style, Modifier::SLOW_BLINK | Modifier::RAPID_BLINK),
+            27 => remove_modifier(style, Modifier::REVERSED),
+            28 => remove_modifier(style, Modifier::HIDDEN),
+            29 => remove_modifier(style, Modifier::CROSSED_OUT),
+            30..=37 => style.fg = Some(ansi_color(param - 30)),
+            38 => style.fg = extended_color(&mut iter),
+            39 => style.fg = None,
+            40..=47 => style.bg = Some(ansi_color(param - 40)),
+            48 => style.bg = extended_color(&mut iter),
+            49 => style.bg = None,
+            90..=97 => style.fg = Some(ansi_color(param - 90 + 8)),
+            100..=107 => style.bg = Some(ansi_color(param - 100 + 8)),
+            _ => {}
+        }
+    }
+}
+
+fn add_modifier(style: &mut Style, modifier: Modifier) {
+    style.add_modifier.insert(modifier);
+}
+
+fn remove_modifier(style: &mut Style, modifier: Modifier) {
+    style.add_modifier.remove(modifier);
+}
+
+fn extended_color(iter: &mut impl Iterator<Item = Option<u8>>) -> Option<Color> {
+    match iter.next()?? {
+        5 => iter.next()?.map(Color::Indexed),
+        2 => {
+            let r = iter.next()??;
+            let g = iter.next()??;
+            let b = iter.next()??;
+            Some(Color::Rgb(r, g, b))
+        }
+        _ => None,
+    }
+}
+
+fn ansi_color(n: u8) -> Color {
+    ANSI_COLORS[(n as usize).min(15)]
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorDepth {
+    #[default]
+    TrueColor,
+    Ansi256,
+    Ansi16,
+    NoColor,
+}
+
+pub fn spans_to_ansi<'a>(spans: &'a [Span<'a>]) -> AnsiExport<'a> {
+    let line = spans
+        .iter()
+        .map(|s| (s.content.as_ref(), s.style))
+        .collect();
+    AnsiExport::new(vec![line])
+}
+
+pub fn lines_to_ansi<'a>(lines: &'a [Line<'a>]) -> AnsiExport<'a> {
+    let lines = lines
+        .iter()
+        .map(|line| {
+            line.spans
+                .iter()
+                .map(|s| (s.content.as_ref(), line.style.patch(s.style)))
+                .collect()
+        })
+        .collect();
+    AnsiExport::new(lines)
+}
+
+pub fn buffer_to_ansi(buf: &Buffer, area: Rect) -> AnsiExport<'_> {
+    let area = area.intersection(buf.area);
+    let lines = area
+        .rows()
+        .map(|row| {
+            let mut line = Vec::new();
+            let mut skip = 0;
+            for x in row.left()..row.right() {
+                let cell = &buf[(x, row.y)];
+                if skip > 0 {
+                    skip -= 1;
+                    continue;
+                }
+                let symbol = cell.symbol();
+                skip = console::measure_text_width(symbol).saturating_sub(1);
+                line.push((symbol, cell.style()));
+            }
+            line
+        })
+        .collect();
+    AnsiExport::new(lines)
+}
+
+pub struct AnsiExport<'a> {
+    lines: Vec<Vec<(&'a str, Style)>>,
+    color_depth: ColorDepth,
+}
+
+impl<'a> AnsiExport<'a> {
+    fn new(lines: Vec<Vec<(&'a str, Style)>>) -> Self {
+        Self {
+            lines,
+            color_depth: ColorDepth::default(),
+        }
+    }
+
+    pub fn color_depth(mut self, color_depth: ColorDepth) -> Self {
+        self.color_depth = color_depth;
+        self
+    }
+
+    pub fn into_string(self) -> String {
+        let mut ret = String::new();
+        for (i, line) in self.lines.iter().enumerate() {
+            if i > 0 {
+                ret.push('\n');
+            }
+            let mut current = String::new();
+            for (text, style) in line {
+                if text.is_empty() {
+                    continue;
+                }
+                let sgr = style_to_sgr(*style, self.color_depth);
+                if sgr != current {
+                    if !current.is_empty() {
+                        ret.push_str("\x1b[0m");
+                    }
+                    if !sgr.is_empty() {
+                        ret.push_str(&format!("\x1b[{sgr}m"));
+                    }
+                    current = sgr;
+                }
+                ret.push_str(text);
+            }
+            if !current.is_empty() {
+                ret.push_str("\x1b[0m");
+            }
+        }
+        ret
+    }
+}
+
+fn style_to_sgr(style: Style, color_depth: ColorDepth) -> String {
+    const MODIFIERS: [(Modifier, &str); 9] = [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::SLOW_BLINK, "5"),
+        (Modifier::RAPID_BLINK, "6"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::HIDDEN, "8"),
+        (Modifier::CROSSED_OUT, "9"),
+    ];
+
+    let modifier = style.add_modifier - style.sub_modifier;
+    let mut params: Vec<String> = MODIFIERS
+        .iter()
+        .filter(|(m, _)| modifier.contains(*m))
+        .map(|(_, p)| p.to_string())
+        .collect();
+    if let Some(fg) = style.fg.and_then(|c| color_to_sgr(c, color_depth, false)) {
+        params.push(fg);
+    }
+    if let Some(bg) = style.bg.and_then(|c| color_to_sgr(c, color_depth, true)) {
+        params.push(bg);
+    }
+    params.join(";")
+}
+
+fn color_to_sgr(color: Color, color_depth: ColorDepth, bg: bool) -> Option<String> {
+    let color = match (color_depth, color) {
+        (ColorDepth::NoColor, _) | (_, Color::Reset) => return None,
+        (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(rgb_to_ansi256(r, g, b)),
+        (ColorDepth::Ansi16, Color::Rgb(r, g, b)) => ansi_color(rgb_to_ansi16(r, g, b)),
+        (ColorDepth::Ansi16, Color::Indexed(n)) => {
+            let (r, g, b) = ansi256_to_rgb(n);
+            ansi_color(rgb_to_ansi16(r, g, b))
+        }
+        (_, color) => color,
+    };
+
+    let base = if bg { 40 } else { 30 };
+    let param = match color {
+        Color::Rgb(r, g, b) => format!("{};2;{r};{g};{b}", base + 8),
+        Color::Indexed(n) => format!("{};5;{n}", base + 8),
+        color => {
+            let n = ANSI_COLORS.iter().position(|c| *c == color)? as u8;
+            if n < 8 {
+                (base + n).to_string()
+            } else {
+                (base + 60 + n - 8).to_string()
+            }
+        }
+    };
+    Some(param)
+}
+
+const ANSI_COLORS: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+const ANSI16_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn ansi256_to_rgb(n: u8) -> (u8, u8, u8) {
+    match n {
+        0..=15 => ANSI16_RGB[n as usize],
+        16..=231 => {
+            let n = n - 16;
+            (
+                CUBE_LEVELS[(n / 36) as usize],
+                CUBE_LEVELS[(n / 6 % 6) as usize],
+                CUBE_LEVELS[(n % 6) as usize],
+            )
+        }
+        _ => {
+            let v = 8 + (n - 232) * 10;
+            (v, v, v)
+        }
+    }
+}
+
+fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    (16..=255)
+        .min_by_key(|n| color_distance((r, g, b), ansi256_to_rgb(*n)))
+        .unwrap()
+}
+
+fn rgb_to_ansi16(r: u8, g: u8, b: u8) -> u8 {
+    (0..16)
+        .min_by_key(|n| color_distance((r, g, b), ANSI16_RGB[*n as usize]))
+        .unwrap()
+}
+
+fn color_distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
 }
 
 #[cfg(test)]
 mod tests {
+    use ratatui_core::style::Stylize;
     use rstest::*;
 
     use super::*;
@@ -207,4 +1049,325 @@ mod tests {
     fn style(fg: Color, bg: Color, modifier: Modifier) -> Style {
         Style::default().fg(fg).bg(bg).add_modifier(modifier)
     }
+
+    #[rstest]
+    #[case(TruncatePosition::Start, 9, "", vec![Span::raw("abc"), Span::raw("def"), Span::raw("ghi")])]
+    #[case(TruncatePosition::Start, 8, "", vec![Span::raw("bc"), Span::raw("def"), Span::raw("ghi")])]
+    #[case(TruncatePosition::Start, 5, "..", vec![Span::raw(".."), Span::raw("ghi")])]
+    #[case(TruncatePosition::Start, 6, "..", vec![Span::raw(".."), Span::raw("f"), Span::raw("ghi")])]
+    #[case(TruncatePosition::Start, 2, "...", vec![Span::raw("..")])]
+    #[case(TruncatePosition::Middle, 9, "..", vec![Span::raw("abc"), Span::raw("def"), Span::raw("ghi")])]
+    #[case(TruncatePosition::Middle, 8, "..", vec![Span::raw("abc"), Span::raw(".."), Span::raw("ghi")])]
+    #[case(TruncatePosition::Middle, 7, "..", vec![Span::raw("abc"), Span::raw(".."), Span::raw("hi")])]
+    #[case(TruncatePosition::Middle, 5, "", vec![Span::raw("abc"), Span::raw("hi")])]
+    #[case(TruncatePosition::Middle, 1, "..", vec![Span::raw(".")])]
+    fn test_truncate_spans_position(
+        #[case] position: TruncatePosition,
+        #[case] max_width: usize,
+        #[case] ellipsis: &str,
+        #[case] expected: Vec<Span>,
+    ) {
+        let spans = vec![Span::raw("abc"), Span::raw("def"), Span::raw("ghi")];
+        let actual = truncate_spans(spans, max_width)
+            .ellipsis(ellipsis)
+            .position(position)
+            .into_spans();
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case(TruncatePosition::Start, 5, vec![Span::raw("…"), Span::raw("本語")])]
+    #[case(TruncatePosition::Start, 4, vec![Span::raw("…"), Span::raw("語")])]
+    #[case(TruncatePosition::Middle, 5, vec![Span::raw("日"), Span::raw("…"), Span::raw("語")])]
+    #[case(TruncatePosition::Middle, 6, vec![Span::raw("日本語")])]
+    fn test_truncate_spans_position_wide_chars(
+        #[case] position: TruncatePosition,
+        #[case] max_width: usize,
+        #[case] expected: Vec<Span>,
+    ) {
+        let spans = vec![Span::raw("日本語")];
+        let actual = truncate_spans(spans, max_width)
+            .ellipsis("…")
+            .position(position)
+            .into_spans();
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case("plain", vec![Span::raw("plain")])]
+    #[case("", vec![])]
+    #[case("\x1b[31mred\x1b[0m normal", vec![Span::raw("red").fg(Color::Red), Span::raw(" normal")])]
+    #[case("\x1b[1;4mbold\x1b[22mund\x1b[mx", vec![
+        Span::raw("bold").add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+        Span::raw("und").add_modifier(Modifier::UNDERLINED),
+        Span::raw("x"),
+    ])]
+    #[case("\x1b[3;92;44mi\x1b[39;49mj", vec![
+        Span::raw("i").fg(Color::LightGreen).bg(Color::Blue).add_modifier(Modifier::ITALIC),
+        Span::raw("j").add_modifier(Modifier::ITALIC),
+    ])]
+    #[case("\x1b[38;5;208ma\x1b[48;2;10;20;30mb", vec![
+        Span::raw("a").fg(Color::Indexed(208)),
+        Span::raw("b").fg(Color::Indexed(208)).bg(Color::Rgb(10, 20, 30)),
+    ])]
+    #[case("\x1b[2Kclear\x1b[1Gline", vec![Span::raw("clear"), Span::raw("line")])]
+    #[case("\x1b[7m\x1b[0m", vec![])]
+    fn test_ansi_to_spans(#[case] s: &str, #[case] expected: Vec<Span>) {
+        assert_eq!(ansi_to_spans(s), expected);
+    }
+
+    #[rstest]
+    #[case("a\nb", vec![Line::from("a"), Line::from("b")])]
+    #[case("a\r\nb\r\n", vec![Line::from("a"), Line::from("b")])]
+    #[case("a\n\nb", vec![Line::from("a"), Line::default(), Line::from("b")])]
+    #[case("", vec![Line::default()])]
+    #[case("\x1b[32m+ added\nstill green\x1b[0m\nplain", vec![
+        Line::from(Span::raw("+ added").fg(Color::Green)),
+        Line::from(Span::raw("still green").fg(Color::Green)),
+        Line::from("plain"),
+    ])]
+    #[case("\x1b[1mx\x1b[0m\n", vec![Line::from(Span::raw("x").add_modifier(Modifier::BOLD))])]
+    #[case("a\n\x1b[0m", vec![Line::from("a")])]
+    #[case("a\r\n\x1b[32m\x1b[0m", vec![Line::from("a")])]
+    #[case("a\n\x1b[32mb", vec![Line::from("a"), Line::from(Span::raw("b").fg(Color::Green))])]
+    #[case("\n", vec![Line::default()])]
+    #[case("\x1b[0m", vec![Line::default()])]
+    fn test_ansi_to_lines(#[case] s: &str, #[case] expected: Vec<Line>) {
+        assert_eq!(ansi_to_lines(s), expected);
+    }
+
+    #[test]
+    fn test_ansi_to_spans_truncate() {
+        let spans = ansi_to_spans("\x1b[31mhello\x1b[0m world");
+        let actual = truncate_spans(spans, 8).ellipsis("…").into_spans();
+        let expected = vec![
+            Span::raw("hello").fg(Color::Red),
+            Span::raw(" w"),
+            Span::raw("…"),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case(vec![Span::raw("plain")], ColorDepth::TrueColor, "plain")]
+    #[case(vec![Span::raw("a").fg(Color::Red), Span::raw("b")], ColorDepth::TrueColor, "\x1b[31ma\x1b[0mb")]
+    #[case(vec![Span::raw("a").fg(Color::Red), Span::raw("b").fg(Color::Red)], ColorDepth::TrueColor, "\x1b[31mab\x1b[0m")]
+    #[case(vec![Span::raw("a").bold().on_light_blue()], ColorDepth::TrueColor, "\x1b[1;104ma\x1b[0m")]
+    #[case(vec![Span::raw("a").fg(Color::Rgb(255, 135, 0)).bg(Color::Indexed(17))], ColorDepth::TrueColor, "\x1b[38;2;255;135;0;48;5;17ma\x1b[0m")]
+    #[case(vec![Span::raw("a").fg(Color::Rgb(255, 135, 0))], ColorDepth::Ansi256, "\x1b[38;5;208ma\x1b[0m")]
+    #[case(vec![Span::raw("a").fg(Color::Rgb(250, 10, 10))], ColorDepth::Ansi16, "\x1b[91ma\x1b[0m")]
+    #[case(vec![Span::raw("a").bg(Color::Indexed(22))], ColorDepth::Ansi16, "\x1b[40ma\x1b[0m")]
+    #[case(vec![Span::raw("a").fg(Color::Red).italic()], ColorDepth::NoColor, "\x1b[3ma\x1b[0m")]
+    #[case(vec![Span::raw("a").fg(Color::Red)], ColorDepth::NoColor, "a")]
+    #[case(vec![Span::raw("a").fg(Color::Reset)], ColorDepth::TrueColor, "a")]
+    fn test_spans_to_ansi(
+        #[case] spans: Vec<Span>,
+        #[case] color_depth: ColorDepth,
+        #[case] expected: &str,
+    ) {
+        let actual = spans_to_ansi(&spans).color_depth(color_depth).into_string();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_spans_to_ansi_round_trip() {
+        let spans = vec![
+            Span::raw("red").fg(Color::Red),
+            Span::raw(" "),
+            Span::raw("bold").bold().fg(Color::Indexed(208)),
+            Span::raw("rgb").bg(Color::Rgb(1, 2, 3)).underlined(),
+        ];
+        let ansi = spans_to_ansi(&spans).into_string();
+        assert_eq!(ansi_to_spans(&ansi), spans);
+    }
+
+    #[test]
+    fn test_lines_to_ansi() {
+        let lines = vec![
+            Line::from(vec![Span::raw("a"), Span::raw("b").bold()]).fg(Color::Green),
+            Line::from("c"),
+        ];
+        let actual = lines_to_ansi(&lines).into_string();
+        assert_eq!(actual, "\x1b[32ma\x1b[0m\x1b[1;32mb\x1b[0m\nc");
+    }
+
+    #[test]
+    fn test_buffer_to_ansi() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 6, 3));
+        buf.set_string(0, 0, "ab", Style::default().fg(Color::Red));
+        buf.set_string(2, 0, "日本", Style::default());
+        buf.set_string(0, 1, "xyz", Style::default().reversed());
+
+        let actual = buffer_to_ansi(&buf, buf.area).into_string();
+        assert_eq!(
+            actual,
+            "\x1b[31mab\x1b[0m日本\n\x1b[7mxyz\x1b[0m   \n      "
+        );
+
+        let actual = buffer_to_ansi(&buf, Rect::new(1, 1, 10, 1)).into_string();
+        assert_eq!(actual, "\x1b[7myz\x1b[0m   ");
+    }
+
+    #[rstest]
+    #[case(Alignment::Left, 8, vec![Span::raw("ab").red(), Span::raw("cd"), Span::raw("....")])]
+    #[case(Alignment::Right, 8, vec![Span::raw("...."), Span::raw("ab").red(), Span::raw("cd")])]
+    #[case(Alignment::Center, 7, vec![Span::raw("."), Span::raw("ab").red(), Span::raw("cd"), Span::raw("..")])]
+    #[case(Alignment::Center, 4, vec![Span::raw("ab").red(), Span::raw("cd")])]
+    #[case(Alignment::Right, 3, vec![Span::raw("ab").red(), Span::raw("~").blue()])]
+    #[case(Alignment::Left, 0, vec![])]
+    fn test_align_spans(
+        #[case] alignment: Alignment,
+        #[case] width: usize,
+        #[case] expected: Vec<Span>,
+    ) {
+        let spans = vec![Span::raw("ab").red(), Span::raw("cd")];
+        let actual = align_spans(spans, width, alignment)
+            .fill('.')
+            .ellipsis("~")
+            .ellipsis_fg(Color::Blue)
+            .into_spans();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_pad_spans() {
+        let actual = pad_spans(vec![Span::raw("abc")], 6)
+            .fill_bg(Color::Gray)
+            .into_spans();
+        let expected = vec![Span::raw("abc"), Span::raw("   ").bg(Color::Gray)];
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case(vec![Span::raw("日本語")], 5, ' ', "日本 ")]
+    #[case(vec![Span::raw("日本語")], 7, ' ', "日本語 ")]
+    #[case(vec![Span::raw("ab")], 5, '-', "ab---")]
+    #[case(vec![Span::raw("ab")], 5, '－', "ab   ")]
+    #[case(vec![Span::raw("ab")], 5, '\u{301}', "ab   ")]
+    #[case(vec![Span::raw("ab")], 5, '\t', "ab   ")]
+    fn test_pad_spans_exact_width(
+        #[case] spans: Vec<Span>,
+        #[case] width: usize,
+        #[case] fill: char,
+        #[case] expected: &str,
+    ) {
+        let actual = pad_spans(spans, width).fill(fill).into_spans();
+        let s: String = actual.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(s, expected);
+        assert_eq!(spans_width(&actual), width);
+    }
+
+    #[test]
+    fn test_align_spans_truncate_position() {
+        let actual = align_spans(vec![Span::raw("abcdefgh")], 5, Alignment::Right)
+            .ellipsis("…")
+            .truncate(TruncatePosition::Start)
+            .into_spans();
+        assert_eq!(actual, vec![Span::raw("…"), Span::raw("efgh")]);
+    }
+
+    #[rstest]
+    #[case(vec![], vec![])]
+    #[case(vec![Span::raw("")], vec![])]
+    #[case(vec![Span::raw("a"), Span::raw("b")], vec![Span::raw("ab")])]
+    #[case(vec![Span::raw("a"), Span::raw(""), Span::raw("b").red(), Span::raw("c").red()], vec![Span::raw("a"), Span::raw("bc").red()])]
+    #[case(vec![Span::raw("a").red(), Span::raw("").blue(), Span::raw("b").red()], vec![Span::raw("ab").red()])]
+    #[case(vec![Span::raw("a").red(), Span::raw("b").bold(), Span::raw("c").red()], vec![Span::raw("a").red(), Span::raw("b").bold(), Span::raw("c").red()])]
+    fn test_normalize_spans(#[case] spans: Vec<Span>, #[case] expected: Vec<Span>) {
+        assert_eq!(normalize_spans(spans).into_spans(), expected);
+    }
+
+    #[test]
+    fn test_normalize_spans_source() {
+        let source = "hello world hello";
+        let spans = vec![
+            Span::raw(&source[0..2]),
+            Span::raw(source[2..5].to_string()),
+            Span::raw(&source[5..6]).red(),
+            Span::raw("WORLD ".to_string()),
+            Span::raw(source[12..17].to_string()).bold(),
+        ];
+        let actual = normalize_spans(spans).source(source).into_spans();
+        assert_eq!(
+            actual,
+            vec![
+                Span::raw("hello"),
+                Span::raw(" ").red(),
+                Span::raw("WORLD "),
+                Span::raw("hello").bold(),
+            ]
+        );
+        let borrowed: Vec<Option<usize>> = actual
+            .iter()
+            .map(|s| match s.content {
+                Cow::Borrowed(c) => Some(c.as_ptr() as usize - source.as_ptr() as usize),
+                Cow::Owned(_) => None,
+            })
+            .collect();
+        assert_eq!(borrowed, vec![Some(0), Some(5), None, Some(12)]);
+    }
+
+    #[test]
+    fn test_normalize_spans_source_mismatch() {
+        let source = "ab ab";
+        let spans = vec![
+            Span::raw("xx".to_string()),
+            Span::raw(" ab".to_string()),
+            Span::raw("ab".to_string()).red(),
+        ];
+        let actual = normalize_spans(spans).source(source).into_spans();
+        assert_eq!(actual, vec![Span::raw("xx ab"), Span::raw("ab").red()]);
+        assert!(actual.iter().all(|s| matches!(s.content, Cow::Owned(_))));
+    }
+
+    #[rstest]
+    #[case(vec![Span::raw("plain")], vec![Span::raw("plain")])]
+    #[case(vec![Span::raw("a\tb")], vec![Span::raw("a"), Span::raw("       "), Span::raw("b")])]
+    #[case(vec![Span::raw("abcd\tb"), Span::raw("\tc").red()], vec![
+        Span::raw("abcd"), Span::raw("    "), Span::raw("b"), Span::raw("       ").red(), Span::raw("c").red(),
+    ])]
+    #[case(vec![Span::raw("\t")], vec![Span::raw("        ")])]
+    #[case(vec![Span::raw("line\r")], vec![Span::raw("line"), Span::raw("^M").dark_gray()])]
+    #[case(vec![Span::raw("a\x00b\x7f").bold()], vec![
+        Span::raw("a").bold(), Span::raw("^@").bold().dark_gray(), Span::raw("b").bold(), Span::raw("^?").bold().dark_gray(),
+    ])]
+    #[case(vec![Span::raw("x\u{85}")], vec![Span::raw("x"), Span::raw("<85>").dark_gray()])]
+    #[case(vec![Span::raw("\x1b[31mred\x1b[0m\x1b]0;title\x07!")], vec![Span::raw("red"), Span::raw("!")])]
+    #[case(vec![Span::raw("\x1bx")], vec![Span::raw("^[").dark_gray(), Span::raw("x")])]
+    #[case(vec![Span::raw("a\x1b["), Span::raw("31mb")], vec![Span::raw("a"), Span::raw("b")])]
+    #[case(vec![Span::raw("a\x1b").red(), Span::raw("[0"), Span::raw("mb\x1b").bold()], vec![
+        Span::raw("a").red(), Span::raw("b").bold(), Span::raw("^[").bold().dark_gray(),
+    ])]
+    fn test_sanitize_spans(#[case] spans: Vec<Span>, #[case] expected: Vec<Span>) {
+        let actual = sanitize_spans(spans)
+            .control_fg(Color::DarkGray)
+            .into_spans();
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case(4, 0, "日\tx", "日  x")]
+    #[case(4, 2, "\tx", "  x")]
+    #[case(4, 0, "a\r\tb", "a^M b")]
+    #[case(0, 0, "a\tb", "ab")]
+    fn test_sanitize_spans_tab_stops(
+        #[case] tab_width: usize,
+        #[case] start_column: usize,
+        #[case] s: &str,
+        #[case] expected: &str,
+    ) {
+        let actual = sanitize_spans(vec![Span::raw(s)])
+            .tab_width(tab_width)
+            .start_column(start_column)
+            .into_spans();
+        let actual: String = actual.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_sanitize_spans_width() {
+        let spans = sanitize_spans(vec![Span::raw("ab\tc\x1b[1md\r")]).into_spans();
+        let truncated = truncate_spans(spans, 10).ellipsis("…").into_spans();
+        assert_eq!(spans_width(&truncated), 10);
+    }
 }
@@ -0,0 +1,438 @@
+use ratatui_core::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Widget,
+};
+
+use crate::spans::{spans_width, truncate_spans};
+
+const POWERLINE_RIGHT: &str = "\u{e0b0}";
+const POWERLINE_LEFT: &str = "\u{e0b2}";
+const GROUP_GAP: usize = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusSection<'a> {
+    spans: Vec<Span<'a>>,
+    style: Style,
+    priority: u8,
+}
+
+impl<'a> StatusSection<'a> {
+    pub fn new<T>(content: T) -> Self
+    where
+        T: Into<Line<'a>>,
+    {
+        let line = content.into();
+        Self {
+            spans: line.spans,
+            style: line.style,
+            priority: 0,
+        }
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn fg(mut self, color: Color) -> Self {
+        self.style = self.style.fg(color);
+        self
+    }
+
+    pub fn bg(mut self, color: Color) -> Self {
+        self.style = self.style.bg(color);
+        self
+    }
+
+    pub fn priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    fn width(&self) -> usize {
+        spans_width(&self.spans)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum StatusSeparator<'a> {
+    #[default]
+    None,
+    Text(Span<'a>),
+    Powerline,
+}
+
+impl StatusSeparator<'_> {
+    fn width(&self) -> usize {
+        match self {
+            StatusSeparator::None => 0,
+            StatusSeparator::Text(span) => console::measure_text_width(&span.content),
+            StatusSeparator::Powerline => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Group {
+    Left,
+    Right,
+    Center,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StatusBar<'a> {
+    mode: Option<StatusSection<'a>>,
+    left: Vec<StatusSection<'a>>,
+    center: Vec<StatusSection<'a>>,
+    right: Vec<StatusSection<'a>>,
+    style: Style,
+    separator: StatusSeparator<'a>,
+    ellipsis: &'a str,
+}
+
+impl<'a> StatusBar<'a> {
+    pub fn new() -> Self {
+        Self {
+            ellipsis: "…",
+            ..Default::default()
+        }
+    }
+
+    pub fn mode(mut self, label: &'a str, style: Style) -> Self {
+        let section = StatusSection::new(vec![Span::raw(" "), Span::raw(label), Span::raw(" ")]);
+        self.mode = Some(section.style(style).priority(u8::MAX));
+        self
+    }
+
+    pub fn left(mut self, section: StatusSection<'a>) -> Self {
+        self.left.push(section);
+        self
+    }
+
+    pub fn center(mut self, section: StatusSection<'a>) -> Self {
+        self.center.push(section);
+        self
+    }
+
+    pub fn right(mut self, section: StatusSection<'a>) -> Self {
+        self.right.push(section);
+        self
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn separator(mut self, separator: StatusSeparator<'a>) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    pub fn ellipsis(mut self, ellipsis: &'a str) -> Self {
+        self.ellipsis = ellipsis;
+        self
+    }
+
+    fn separator_count(&self, group: Group, count: usize) -> usize {
+        match (&self.separator, group) {
+            (StatusSeparator::Powerline, Group::Left | Group::Right) => count,
+            _ => count.saturating_sub(1),
+        }
+    }
+
+    fn shrink(&self, sections: &mut [(Group, StatusSection<'a>, usize)], max_width: usize) {
+        let separator_width = self.separator.width();
+        let total_width = |sections: &[(Group, StatusSection<'a>, usize)]| {
+            let mut total = 0;
+            let mut groups: usize = 0;
+            for group in [Group::Left, Group::Center, Group::Right] {
+                let widths: Vec<usize> = sections
+                    .iter()
+                    .filter(|(g, _, w)| *g == group && *w > 0)
+                    .map(|(_, _, w)| *w)
+                    .collect();
+                if widths.is_empty() {
+                    continue;
+                }
+                total += widths.iter().sum::<usize>()
+                    + self.separator_count(group, widths.len()) * separator_width;
+                groups += 1;
+            }
+            total + GROUP_GAP * groups.saturating_sub(1)
+        };
+
+        let ellipsis_width = console::measure_text_width(self.ellipsis);
+        loop {
+            let total = total_width(sections);
+            if total <= max_width {
+                break;
+            }
+            let Some((_, _, width)) = sections
+                .iter_mut()
+                .enumerate()
+                .filter(|(_, (_, _, w))| *w > 0)
+                .min_by_key(|(i, (g, s, _))| (s.priority, std::cmp::Reverse((*g, *i))))
+                .map(|(_, s)| s)
+            else {
+                break;
+            };
+            let excess = total - max_width;
+            if *width > excess + ellipsis_width {
+                *width -= excess;
+            } else {
+                *width = 0;
+            }
+        }
+    }
+
+    fn group_spans(
+        &self,
+        group: Group,
+        sections: &[(Group, StatusSection<'a>, usize)],
+    ) -> Vec<Span<'a>> {
+        let sections: Vec<(Style, Vec<Span<'a>>)> = sections
+            .iter()
+            .filter(|(g, _, w)| *g == group && *w > 0)
+            .map(|(_, s, w)| {
+                let spans = s
+                    .spans
+                    .iter()
+                    .map(|span| Span::styled(span.content.clone(), s.style.patch(span.style)))
+                    .collect();
+                let spans = truncate_spans(spans, *w)
+                    .ellipsis(self.ellipsis)
+                    .ellipsis_style(s.style)
+                    .into_spans();
+                (s.style, spans)
+            })
+            .collect();
+
+        let mut ret = Vec::new();
+        let n = sections.len();
+        for (i, (style, spans)) in sections.iter().enumerate() {
+            match &self.separator {
+                StatusSeparator::Powerline if group == Group::Right => {
+                    let prev_bg = if i == 0 {
+                        self.style.bg
+                    } else {
+                        sections[i - 1].0.bg
+                    };
+                    ret.push(powerline_span(POWERLINE_LEFT, style.bg, prev_bg));
+                }
+                StatusSeparator::Powerline if group == Group::Center && i > 0 => {
+                    ret.push(powerline_span(
+                        POWERLINE_RIGHT,
+                        sections[i - 1].0.bg,
+                        style.bg,
+                    ));
+                }
+                StatusSeparator::Text(separator) if i > 0 => {
+                    ret.push(separator.clone());
+                }
+                _ => {}
+            }
+            ret.extend(spans.iter().cloned());
+            if self.separator == StatusSeparator::Powerline && group == Group::Left {
+                let next_bg = if i + 1 < n {
+                    sections[i + 1].0.bg
+                } else {
+                    self.style.bg
+                };
+                ret.push(powerline_span(POWERLINE_RIGHT, style.bg, next_bg));
+            }
+        }
+        ret
+    }
+}
+
+fn powerline_span(symbol: &'static str, fg: Option<Color>, bg: Option<Color>) -> Span<'static> {
+    let style = Style {
+        fg: Some(fg.unwrap_or(Color::Reset)),
+        bg: Some(bg.unwrap_or(Color::Reset)),
+        ..Style::default()
+    };
+    Span::styled(symbol, style)
+}
+
+impl Widget for StatusBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+        let area = Rect { height: 1, ..area };
+        buf.set_style(area, self.style);
+
+        let mut sections: Vec<(Group, StatusSection<'_>, usize)> = Vec::new();
+        let left = self.mode.iter().chain(self.left.iter());
+        for (group, group_sections) in [
+            (Group::Left, left.collect::<Vec<_>>()),
+            (Group::Center, self.center.iter().collect()),
+            (Group::Right, self.right.iter().collect()),
+        ] {
+            for s in group_sections {
+                sections.push((group, s.clone(), s.width()));
+            }
+        }
+        self.shrink(&mut sections, area.width as usize);
+
+        let left = self.group_spans(Group::Left, &sections);
+        let center = self.group_spans(Group::Center, &sections);
+        let right = self.group_spans(Group::Right, &sections);
+
+        let gap = |spans: &[Span<'_>]| {
+            if spans.is_empty() {
+                0
+            } else {
+                GROUP_GAP as u16
+            }
+        };
+        let width = |spans: &[Span<'_>]| u16::try_from(spans_width(spans)).unwrap_or(u16::MAX);
+        let left_end = area
+            .x
+            .saturating_add(width(&left))
+            .saturating_add(gap(&left));
+        let right_start = area.right().saturating_sub(width(&right));
+        let center_width = width(&center);
+        let center_x = (area.x + (area.width.saturating_sub(center_width)) / 2)
+            .min(right_start.saturating_sub(center_width.saturating_add(gap(&right))))
+            .max(left_end);
+
+        for (x, spans) in [(area.x, left), (center_x, center), (right_start, right)] {
+            let mut x = x;
+            for span in &spans {
+                x = buf
+                    .set_span(x, area.y, span, area.right().saturating_sub(x))
+                    .0;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::*;
+
+    use super::*;
+
+    fn render(bar: StatusBar<'_>, width: u16) -> Buffer {
+        let mut buf = Buffer::empty(Rect::new(0, 0, width, 1));
+        bar.render(buf.area, &mut buf);
+        buf
+    }
+
+    fn content(buf: &Buffer) -> String {
+        buf.content.iter().map(|c| c.symbol()).collect()
+    }
+
+    fn status_bar() -> StatusBar<'static> {
+        StatusBar::new()
+            .left(StatusSection::new("src/main.rs").priority(2))
+            .left(StatusSection::new("[+]").priority(3))
+            .center(StatusSection::new("main").priority(0))
+            .right(StatusSection::new("utf-8").priority(1))
+            .right(StatusSection::new("12:34").priority(4))
+            .separator(StatusSeparator::Text(Span::raw(" ")))
+    }
+
+    #[rstest]
+    #[case(40, "src/main.rs [+]   main       utf-8 12:34")]
+    #[case(32, "src/main.rs [+] main utf-8 12:34")]
+    #[case(30, "src/main.rs [+] m… utf-8 12:34")]
+    #[case(29, "src/main.rs [+]   utf-8 12:34")]
+    #[case(27, "src/main.rs [+] utf-8 12:34")]
+    #[case(24, "src/main.rs [+] u… 12:34")]
+    #[case(22, "src/main.rs [+]  12:34")]
+    #[case(18, "src/mai… [+] 12:34")]
+    #[case(10, "[+]  12:34")]
+    #[case(5, "12:34")]
+    #[case(3, "12…")]
+    fn test_status_bar_shrink(#[case] width: u16, #[case] expected: &str) {
+        let buf = render(status_bar(), width);
+        assert_eq!(content(&buf), expected);
+    }
+
+    #[test]
+    fn test_status_bar_center_pushed_by_left() {
+        let bar = StatusBar::new()
+            .left(StatusSection::new("long left section"))
+            .center(StatusSection::new("mid"))
+            .right(StatusSection::new("r"));
+        let buf = render(bar, 24);
+        assert_eq!(content(&buf), "long left section mid  r");
+    }
+
+    #[test]
+    fn test_status_bar_huge_section() {
+        let huge = "x".repeat(u16::MAX as usize + 10);
+        let bar = StatusBar::new()
+            .left(StatusSection::new(huge.as_str()))
+            .right(StatusSection::new("r"));
+        let buf = render(bar, 8);
+        assert_eq!(content(&buf), "xxxxxxx…");
+    }
+
+    #[test]
+    fn test_status_bar_mode() {
+        let mode_style = Style::default().fg(Color::Black).bg(Color::Green);
+        let bar = StatusBar::new()
+            .mode("NORMAL", mode_style)
+            .left(StatusSection::new("file.txt"))
+            .right(StatusSection::new("1:1"));
+        let buf = render(bar, 20);
+        let mut expected = Buffer::with_lines([" NORMAL file.txt 1:1"]);
+        expected.set_style(Rect::new(0, 0, 8, 1), mode_style);
+        assert_eq!(buf, expected);
+
+        let bar = StatusBar::new()
+            .mode("NORMAL", mode_style)
+            .left(StatusSection::new("file.txt"));
+        let buf = render(bar, 10);
+        assert_eq!(content(&buf), " NORMAL f…");
+    }
+
+    #[test]
+    fn test_status_bar_powerline() {
+        let a = Style::default().fg(Color::Black).bg(Color::Blue);
+        let b = Style::default().fg(Color::White).bg(Color::DarkGray);
+        let bar_style = Style::default().bg(Color::Black);
+        let bar = StatusBar::new()
+            .style(bar_style)
+            .separator(StatusSeparator::Powerline)
+            .left(StatusSection::new(" A ").style(a))
+            .left(StatusSection::new(" B ").style(b))
+            .right(StatusSection::new(" C ").style(b))
+            .right(StatusSection::new(" D ").style(a));
+        let buf = render(bar, 20);
+        assert_eq!(
+            content(&buf),
+            " A \u{e0b0} B \u{e0b0}    \u{e0b2} C \u{e0b2} D "
+        );
+
+        let glyph = |x: u16| buf[(x, 0)].style();
+        assert_eq!(glyph(3).fg, Some(Color::Blue));
+        assert_eq!(glyph(3).bg, Some(Color::DarkGray));
+        assert_eq!(glyph(7).fg, Some(Color::DarkGray));
+        assert_eq!(glyph(7).bg, Some(Color::Black));
+        assert_eq!(glyph(12).fg, Some(Color::DarkGray));
+        assert_eq!(glyph(12).bg, Some(Color::Black));
+        assert_eq!(glyph(16).fg, Some(Color::Blue));
+        assert_eq!(glyph(16).bg, Some(Color::DarkGray));
+        assert_eq!(buf[(1, 0)].style().bg, Some(Color::Blue));
+        assert_eq!(buf[(8, 0)].style().bg, Some(Color::Black));
+    }
+
+    #[test]
+    fn test_status_bar_powerline_shrink() {
+        let bar = StatusBar::new()
+            .separator(StatusSeparator::Powerline)
+            .left(StatusSection::new(" A ").priority(1))
+            .left(StatusSection::new(" B "))
+            .right(StatusSection::new(" C ").priority(1));
+        let buf = render(bar, 10);
+        assert_eq!(content(&buf), " A \u{e0b0}  \u{e0b2} C ");
+    }
+}
@@ -0,0 +1,247 @@
+use ratatui_core::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{self, FontStyle, Theme, ThemeSet},
+    parsing::{SyntaxReference, SyntaxSet},
+};
+
+const DEFAULT_THEME: &str = "base16-ocean.dark";
+
+pub struct SyntaxHighlighter {
+    syntax_set: SyntaxSet,
+    theme_set: ThemeSet,
+    theme: String,
+    background: bool,
+}
+
+impl Default for SyntaxHighlighter {
+    fn default() -> Self {
+        Self::new(
+            SyntaxSet::load_defaults_nonewlines(),
+            ThemeSet::load_defaults(),
+        )
+    }
+}
+
+impl SyntaxHighlighter {
+    pub fn new(syntax_set: SyntaxSet, theme_set: ThemeSet) -> Self {
+        let theme = if theme_set.themes.contains_key(DEFAULT_THEME) {
+            DEFAULT_THEME.to_string()
+        } else {
+            theme_set.themes.keys().next().cloned().unwrap_or_default()
+        };
+        Self {
+            syntax_set,
+            theme_set,
+            theme,
+            background: false,
+        }
+    }
+
+    pub fn theme(mut self, name: &str) -> Self {
+        self.set_theme(name);
+        self
+    }
+
+    pub fn background(mut self, background: bool) -> Self {
+        self.background = background;
+        self
+    }
+
+    pub fn set_theme(&mut self, name: &str) -> bool {
+        if self.theme_set.themes.contains_key(name) {
+            self.theme = name.to_string();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn theme_name(&self) -> &str {
+        &self.theme
+    }
+
+    pub fn theme_names(&self) -> Vec<&str> {
+        self.theme_set.themes.keys().map(|k| k.as_str()).collect()
+    }
+
+    pub fn background_style(&self) -> Style {
+        self.current_theme()
+            .and_then(|t| t.settings.background)
+            .map(|c| Style::default().bg(to_color(c)))
+            .unwrap_or_default()
+    }
+
+    pub fn highlight<'a>(&self, text: &'a str, token: &str) -> Vec<Line<'a>> {
+        let mut highlighter = self.line_highlighter(token);
+        text.lines()
+            .map(|l| highlighter.highlight_line(l))
+            .collect()
+    }
+
+    pub fn line_highlighter(&self, token: &str) -> LineHighlighter<'_> {
+        let syntax = self.find_syntax(token);
+        LineHighlighter {
+            lines: self
+                .current_theme()
+                .map(|theme| HighlightLines::new(syntax, theme)),
+            syntax_set: &self.syntax_set,
+            background: self.background,
+        }
+    }
+
+    fn find_syntax(&self, token: &str) -> &SyntaxReference {
+        self.syntax_set
+            .find_syntax_by_token(token)
+            .unwrap_or_else(|| self.syntax_set.find_syntax_plain_text())
+    }
+
+    fn current_theme(&self) -> Option<&Theme> {
+        self.theme_set.themes.get(&self.theme)
+    }
+}
+
+pub struct LineHighlighter<'a> {
+    lines: Option<HighlightLines<'a>>,
+    syntax_set: &'a SyntaxSet,
+    background: bool,
+}
+
+impl LineHighlighter<'_> {
+    pub fn highlight_line<'b>(&mut self, line: &'b str) -> Line<'b> {
+        let line = line.strip_suffix('\n').unwrap_or(line);
+        let line = line.strip_suffix('\r').unwrap_or(line);
+
+        let Some(ranges) = self
+            .lines
+            .as_mut()
+            .and_then(|h| h.highlight_line(line, self.syntax_set).ok())
+        else {
+            return Line::from(line);
+        };
+
+        let spans: Vec<Span<'b>> = ranges
+            .into_iter()
+            .filter(|(_, s)| !s.is_empty())
+            .map(|(style, s)| Span::styled(s, to_style(style, self.background)))
+            .collect();
+        Line::from(spans)
+    }
+}
+
+fn to_style(style: highlighting::Style, background: bool) -> Style {
+    let mut ret = Style::default().fg(to_color(style.foreground));
+    if background {
+        ret = ret.bg(to_color(style.background));
+    }
+    if style.font_style.contains(FontStyle::BOLD) {
+        ret = ret.add_modifier(Modifier::BOLD);
+    }
+    if style.font_style.contains(FontStyle::ITALIC) {
+        ret = ret.add_modifier(Modifier::ITALIC);
+    }
+    if style.font_style.contains(FontStyle::UNDERLINE) {
+        ret = ret.add_modifier(Modifier::UNDERLINED);
+    }
+    ret
+}
+
+fn to_color(color: highlighting::Color) -> Color {
+    Color::Rgb(color.r, color.g, color.b)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::LazyLock;
+
+    use super::*;
+    use crate::{highlight::highlight_matched_text, spans::truncate_spans};
+
+    static HIGHLIGHTER: LazyLock<SyntaxHighlighter> = LazyLock::new(SyntaxHighlighter::default);
+
+    fn line_to_string(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_highlight() {
+        let text = "fn main() {\n    let x = 1;\n}\n";
+        let lines = HIGHLIGHTER.highlight(text, "rs");
+
+        assert_eq!(lines.len(), 3);
+        let actual: Vec<String> = lines.iter().map(line_to_string).collect();
+        assert_eq!(actual, vec!["fn main() {", "    let x = 1;", "}"]);
+
+        let fn_style = lines[0].spans[0].style;
+        let name_span = lines[0].spans.iter().find(|s| s.content == "main").unwrap();
+        assert!(matches!(fn_style.fg, Some(Color::Rgb(..))));
+        assert_ne!(fn_style.fg, name_span.style.fg);
+        assert!(lines
+            .iter()
+            .flat_map(|l| &l.spans)
+            .all(|s| s.style.bg.is_none()));
+    }
+
+    #[test]
+    fn test_highlight_unknown_syntax() {
+        let lines = HIGHLIGHTER.highlight("plain text", "unknown-ext");
+        assert_eq!(lines.len(), 1);
+        assert_eq!(line_to_string(&lines[0]), "plain text");
+        assert_eq!(lines[0].spans.len(), 1);
+    }
+
+    #[test]
+    fn test_line_highlighter_keeps_state() {
+        let mut highlighter = HIGHLIGHTER.line_highlighter("rs");
+        let first = highlighter.highlight_line("/* comment");
+        let second = highlighter.highlight_line("still comment */\n");
+        assert_eq!(line_to_string(&second), "still comment */");
+
+        let plain = HIGHLIGHTER.highlight("still comment */", "rs");
+        assert_eq!(second.spans[0].style, first.spans[0].style);
+        assert_ne!(second.spans[0].style, plain[0].spans[0].style);
+    }
+
+    #[test]
+    fn test_theme() {
+        let highlighter = SyntaxHighlighter::default();
+        assert_eq!(highlighter.theme_name(), DEFAULT_THEME);
+        assert!(highlighter.theme_names().contains(&"InspiredGitHub"));
+
+        let mut highlighter = highlighter.theme("no-such-theme");
+        assert_eq!(highlighter.theme_name(), DEFAULT_THEME);
+        assert!(highlighter.set_theme("InspiredGitHub"));
+        assert_eq!(highlighter.theme_name(), "InspiredGitHub");
+        assert!(!highlighter.set_theme("no-such-theme"));
+
+        let dark = HIGHLIGHTER.highlight("let x = 1;", "rs");
+        let light = highlighter.background(true).highlight("let x = 1;", "rs");
+        assert_ne!(dark[0].spans[0].style.fg, light[0].spans[0].style.fg);
+        assert!(light[0].spans[0].style.bg.is_some());
+    }
+
+    #[test]
+    fn test_highlight_compatible_with_spans() {
+        let text = "let value = 42;";
+        let lines = HIGHLIGHTER.highlight(text, "rs");
+        let spans = lines[0].spans.clone();
+
+        let truncated = truncate_spans(spans.clone(), 8).ellipsis("…").into_spans();
+        let s: String = truncated.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(s, "let val…");
+
+        let highlighted = highlight_matched_text(spans)
+            .matched_range(4, 9)
+            .matched_modifier(Modifier::REVERSED)
+            .into_spans();
+        let matched: String = highlighted
+            .iter()
+            .filter(|s| s.style.add_modifier.contains(Modifier::REVERSED))
+            .map(|s| s.content.as_ref())
+            .collect();
+        assert_eq!(matched, "value");
+    }
+}
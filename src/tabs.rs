@@ -0,0 +1,539 @@
+use ratatui_core::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{StatefulWidget, Widget},
+};
+use ratatui_crossterm::crossterm::event::{
+    KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+
+use crate::{
+    key_code, key_code_char,
+    keys::{is_press_or_repeat, mouse_position},
+    layout::RectExt,
+    spans::{spans_width, truncate_spans},
+};
+
+type TabContent<'a> = Box<dyn FnOnce(Rect, &mut Buffer) + 'a>;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TabbedViewState {
+    selected: usize,
+    offset: usize,
+    len: usize,
+    tab_areas: Vec<(usize, Rect)>,
+    left_arrow_area: Option<Rect>,
+    right_arrow_area: Option<Rect>,
+    bar_area: Rect,
+    content_area: Rect,
+}
+
+impl TabbedViewState {
+    pub fn new(selected: usize) -> Self {
+        Self {
+            selected,
+            ..Default::default()
+        }
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn select(&mut self, index: usize) {
+        self.selected = index;
+    }
+
+    pub fn select_next(&mut self) {
+        if self.len > 0 {
+            self.selected = (self.selected + 1) % self.len;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if self.len > 0 {
+            self.selected = (self.selected + self.len - 1) % self.len;
+        }
+    }
+
+    pub fn content_area(&self) -> Rect {
+        self.content_area
+    }
+
+    pub fn handle_key_event(&mut self, e: &KeyEvent) -> bool {
+        if !is_press_or_repeat(e) || !e.modifiers.difference(KeyModifiers::SHIFT).is_empty() {
+            return false;
+        }
+        match *e {
+            key_code!(KeyCode::Right) | key_code_char!('l') => {
+                self.select_next();
+                true
+            }
+            key_code!(KeyCode::Left) | key_code_char!('h') => {
+                self.select_prev();
+                true
+            }
+            key_code_char!(c) if ('1'..='9').contains(&c) => {
+                let index = c as usize - '1' as usize;
+                if index < self.len {
+                    self.selected = index;
+                    true
+                } else {
+                    false
+                }
+            }
+            _ => false,
+        }
+    }
+
+    pub fn handle_mouse_event(&mut self, e: &MouseEvent) -> bool {
+        let position = mouse_position(e);
+        let in_bar = |area: Option<Rect>| area.is_some_and(|a| a.contains(position));
+        match e.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if in_bar(self.left_arrow_area) {
+                    self.select_prev();
+                    return true;
+                }
+                if in_bar(self.right_arrow_area) {
+                    self.select_next();
+                    return true;
+                }
+                match self.tab_areas.iter().find(|(_, a)| a.contains(position)) {
+                    Some((i, _)) => {
+                        self.selected = *i;
+                        true
+                    }
+                    None => false,
+                }
+            }
+            MouseEventKind::ScrollDown | MouseEventKind::ScrollRight
+                if self.bar_area.contains(position) =>
+            {
+                self.select_next();
+                true
+            }
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollLeft
+                if self.bar_area.contains(position) =>
+            {
+                self.select_prev();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+pub struct TabbedView<'a> {
+    titles: Vec<Line<'a>>,
+    content: Option<TabContent<'a>>,
+    style: Style,
+    highlight_style: Style,
+    divider: Span<'a>,
+    padding: &'a str,
+    left_arrow: Span<'a>,
+    right_arrow: Span<'a>,
+    max_title_width: Option<usize>,
+    ellipsis: &'a str,
+}
+
+impl<'a> TabbedView<'a> {
+    pub fn new<T>(titles: Vec<T>) -> Self
+    where
+        T: Into<Line<'a>>,
+    {
+        Self {
+            titles: titles.into_iter().map(Into::into).collect(),
+            content: None,
+            style: Style::default(),
+            highlight_style: Style::default().add_modifier(Modifier::REVERSED),
+            divider: Span::raw("|"),
+            padding: " ",
+            left_arrow: Span::raw("<"),
+            right_arrow: Span::raw(">"),
+            max_title_width: None,
+            ellipsis: "…",
+        }
+    }
+
+    pub fn content<W: Widget + 'a>(mut self, content: W) -> Self {
+        self.content = Some(Box::new(move |area, buf| content.render(area, buf)));
+        self
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn highlight_style(mut self, style: Style) -> Self {
+        self.highlight_style = style;
+        self
+    }
+
+    pub fn highlight_fg(mut self, color: Color) -> Self {
+        self.highlight_style = self.highlight_style.fg(color);
+        self
+    }
+
+    pub fn highlight_bg(mut self, color: Color) -> Self {
+        self.highlight_style = self.highlight_style.bg(color);
+        self
+    }
+
+    pub fn highlight_modifier(mut self, modifier: Modifier) -> Self {
+        self.highlight_style = self.highlight_style.add_modifier(modifier);
+        self
+    }
+
+    pub fn divider(mut self, divider: impl Into<Span<'a>>) -> Self {
+        self.divider = divider.into();
+        self
+    }
+
+    pub fn padding(mut self, padding: &'a str) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    pub fn arrows(mut self, left: impl Into<Span<'a>>, right: impl Into<Span<'a>>) -> Self {
+        self.left_arrow = left.into();
+        self.right_arrow = right.into();
+        self
+    }
+
+    pub fn max_title_width(mut self, width: usize) -> Self {
+        self.max_title_width = Some(width);
+        self
+    }
+
+    pub fn ellipsis(mut self, ellipsis: &'a str) -> Self {
+        self.ellipsis = ellipsis;
+        self
+    }
+
+    fn title_spans(&self, index: usize, max_width: usize) -> Vec<Span<'a>> {
+        let title = &self.titles[index];
+        let padding_width = console::measure_text_width(self.padding) * 2;
+        let max_title_width = max_width
+            .saturating_sub(padding_width)
+            .min(self.max_title_width.unwrap_or(usize::MAX));
+        let spans = title
+            .spans
+            .iter()
+            .map(|s| Span::styled(s.content.clone(), title.style.patch(s.style)))
+            .collect();
+        let mut ret = vec![Span::raw(self.padding)];
+        ret.extend(
+            truncate_spans(spans, max_title_width)
+                .ellipsis(self.ellipsis)
+                .into_spans(),
+        );
+        ret.push(Span::raw(self.padding));
+        ret
+    }
+}
+
+impl StatefulWidget for TabbedView<'_> {
+    type State = TabbedViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        if area.is_empty() {
+            return;
+        }
+        let (bar, content_area) = area.split_top(1);
+        state.len = self.titles.len();
+        state.selected = state.selected.min(state.len.saturating_sub(1));
+        state.tab_areas.clear();
+        state.left_arrow_area = None;
+        state.right_arrow_area = None;
+        state.bar_area = bar;
+        state.content_area = content_area;
+
+        buf.set_style(bar, self.style);
+
+        let bar_width = bar.width as usize;
+        let divider_width = console::measure_text_width(&self.divider.content);
+        let widths: Vec<usize> = (0..state.len)
+            .map(|i| spans_width(&self.title_spans(i, usize::MAX)))
+            .collect();
+        let total = widths.iter().sum::<usize>() + divider_width * state.len.saturating_sub(1);
+
+        let tabs_area = if total > bar_width {
+            let (left, rest) = bar.split_left(1);
+            let (tabs_area, right) = rest.split_right(1);
+            state.offset = calc_offset(
+                &widths,
+                divider_width,
+                state.offset,
+                state.selected,
+                tabs_area.width as usize,
+            );
+            if state.offset > 0 {
+                buf.set_span(left.x, left.y, &self.left_arrow, left.width);
+                state.left_arrow_area = Some(left);
+            }
+            state.right_arrow_area = Some(right);
+            tabs_area
+        } else {
+            state.offset = 0;
+            bar
+        };
+
+        let mut x = tabs_area.x;
+        for (i, width) in widths.iter().enumerate().skip(state.offset) {
+            if i > state.offset {
+                if x as usize + divider_width + width > tabs_area.right() as usize {
+                    break;
+                }
+                x = buf
+                    .set_span(x, bar.y, &self.divider, tabs_area.right() - x)
+                    .0;
+            }
+            let rest = (tabs_area.right() - x) as usize;
+            if rest == 0 {
+                break;
+            }
+            let mut spans = self.title_spans(i, rest);
+            if i == state.selected {
+                for span in &mut spans {
+                    span.style = span.style.patch(self.highlight_style);
+                }
+            }
+            let start = x;
+            for span in &spans {
+                x = buf.set_span(x, bar.y, span, tabs_area.right() - x).0;
+            }
+            state
+                .tab_areas
+                .push((i, Rect::new(start, bar.y, x - start, 1)));
+        }
+
+        match (state.right_arrow_area, state.tab_areas.last()) {
+            (Some(right), Some((last, _))) if *last + 1 < state.len => {
+                buf.set_span(right.x, right.y, &self.right_arrow, right.width);
+            }
+            _ => state.right_arrow_area = None,
+        }
+
+        if let Some(content) = self.content.take() {
+            content(content_area, buf);
+        }
+    }
+}
+
+fn calc_offset(
+    widths: &[usize],
+    divider_width: usize,
+    offset: usize,
+    selected: usize,
+    available: usize,
+) -> usize {
+    let mut offset = offset.min(selected);
+    while offset < selected {
+        let used =
+            widths[offset..=selected].iter().sum::<usize>() + divider_width * (selected - offset);
+        if used <= available {
+            break;
+        }
+        offset += 1;
+    }
+    offset
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui_widgets::paragraph::Paragraph;
+
+    use super::*;
+
+    fn titles() -> Vec<&'static str> {
+        vec!["Files", "Search", "Git", "Settings"]
+    }
+
+    fn render(view: TabbedView<'_>, width: u16, state: &mut TabbedViewState) -> Buffer {
+        let mut buf = Buffer::empty(Rect::new(0, 0, width, 2));
+        view.highlight_style(Style::default())
+            .render(buf.area, &mut buf, state);
+        buf
+    }
+
+    fn click(column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn test_tabbed_view_render() {
+        let mut state = TabbedViewState::default();
+        let view = TabbedView::new(titles()).content(Paragraph::new("content"));
+        let buf = render(view, 40, &mut state);
+        assert_eq!(
+            buf,
+            Buffer::with_lines([
+                " Files | Search | Git | Settings        ",
+                "content                                 ",
+            ])
+        );
+        assert_eq!(state.content_area(), Rect::new(0, 1, 40, 1));
+        assert_eq!(
+            state.tab_areas,
+            vec![
+                (0, Rect::new(0, 0, 7, 1)),
+                (1, Rect::new(8, 0, 8, 1)),
+                (2, Rect::new(17, 0, 5, 1)),
+                (3, Rect::new(23, 0, 10, 1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tabbed_view_render_highlight() {
+        let mut state = TabbedViewState::new(1);
+        let highlight = Style::default().fg(Color::Yellow);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 20, 1));
+        TabbedView::new(vec!["A", "B"])
+            .highlight_style(highlight)
+            .render(buf.area, &mut buf, &mut state);
+        let mut expected = Buffer::with_lines([" A | B              "]);
+        expected.set_style(Rect::new(4, 0, 3, 1), highlight);
+        assert_eq!(buf, expected);
+    }
+
+    #[test]
+    fn test_tabbed_view_render_max_title_width() {
+        let mut state = TabbedViewState::default();
+        let view = TabbedView::new(titles()).max_title_width(4).ellipsis("~");
+        let buf = render(view, 30, &mut state);
+        let line: String = (0..30).map(|x| buf[(x, 0)].symbol()).collect();
+        assert_eq!(line, " Fil~ | Sea~ | Git | Set~     ");
+    }
+
+    #[test]
+    fn test_tabbed_view_render_scroll() {
+        let mut state = TabbedViewState::default();
+        let buf = render(TabbedView::new(titles()), 20, &mut state);
+        let line: String = (0..20).map(|x| buf[(x, 0)].symbol()).collect();
+        assert_eq!(line, "  Files | Search   >");
+        assert_eq!(state.left_arrow_area, None);
+        assert_eq!(state.right_arrow_area, Some(Rect::new(19, 0, 1, 1)));
+
+        state.select(3);
+        let buf = render(TabbedView::new(titles()), 20, &mut state);
+        let line: String = (0..20).map(|x| buf[(x, 0)].symbol()).collect();
+        assert_eq!(line, "< Git | Settings    ");
+        assert_eq!(state.left_arrow_area, Some(Rect::new(0, 0, 1, 1)));
+        assert_eq!(state.right_arrow_area, None);
+
+        state.select(2);
+        let buf = render(TabbedView::new(titles()), 20, &mut state);
+        let line: String = (0..20).map(|x| buf[(x, 0)].symbol()).collect();
+        assert_eq!(line, "< Git | Settings    ");
+
+        state.select(0);
+        let buf = render(TabbedView::new(titles()), 20, &mut state);
+        let line: String = (0..20).map(|x| buf[(x, 0)].symbol()).collect();
+        assert_eq!(line, "  Files | Search   >");
+    }
+
+    #[test]
+    fn test_tabbed_view_render_narrow() {
+        let mut state = TabbedViewState::new(3);
+        let buf = render(TabbedView::new(titles()), 8, &mut state);
+        let line: String = (0..8).map(|x| buf[(x, 0)].symbol()).collect();
+        assert_eq!(line, "< Set…  ");
+    }
+
+    #[test]
+    fn test_tabbed_view_key_event() {
+        let mut state = TabbedViewState::default();
+        render(TabbedView::new(titles()), 40, &mut state);
+
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        assert!(state.handle_key_event(&key(KeyCode::Right)));
+        assert_eq!(state.selected(), 1);
+        assert!(state.handle_key_event(&key(KeyCode::Char('h'))));
+        assert!(state.handle_key_event(&key(KeyCode::Left)));
+        assert_eq!(state.selected(), 3);
+        assert!(state.handle_key_event(&key(KeyCode::Char('l'))));
+        assert_eq!(state.selected(), 0);
+        assert!(state.handle_key_event(&key(KeyCode::Char('3'))));
+        assert_eq!(state.selected(), 2);
+        assert!(!state.handle_key_event(&key(KeyCode::Char('5'))));
+        assert!(!state.handle_key_event(&key(KeyCode::Char('x'))));
+        assert_eq!(state.selected(), 2);
+    }
+
+    #[test]
+    fn test_tabbed_view_key_event_modifiers() {
+        let mut state = TabbedViewState::default();
+        render(TabbedView::new(titles()), 40, &mut state);
+
+        let ctrl = |code| KeyEvent::new(code, KeyModifiers::CONTROL);
+        let alt = |code| KeyEvent::new(code, KeyModifiers::ALT);
+        assert!(!state.handle_key_event(&ctrl(KeyCode::Char('l'))));
+        assert!(!state.handle_key_event(&ctrl(KeyCode::Right)));
+        assert!(!state.handle_key_event(&alt(KeyCode::Char('3'))));
+        assert!(!state.handle_key_event(&alt(KeyCode::Char('h'))));
+        assert_eq!(state.selected(), 0);
+
+        let shift = KeyEvent::new(KeyCode::Char('2'), KeyModifiers::SHIFT);
+        assert!(state.handle_key_event(&shift));
+        assert_eq!(state.selected(), 1);
+    }
+
+    #[test]
+    fn test_tabbed_view_render_empty_area() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 10, 2));
+        let mut state = TabbedViewState::default();
+        TabbedView::new(titles()).render(Rect::new(0, 2, 10, 0), &mut buf, &mut state);
+        assert_eq!(buf, Buffer::empty(Rect::new(0, 0, 10, 2)));
+    }
+
+    #[test]
+    fn test_tabbed_view_mouse_event() {
+        let mut state = TabbedViewState::default();
+        render(TabbedView::new(titles()), 20, &mut state);
+
+        assert!(state.handle_mouse_event(&click(12, 0)));
+        assert_eq!(state.selected(), 1);
+        assert!(!state.handle_mouse_event(&click(12, 1)));
+        assert!(state.handle_mouse_event(&click(19, 0)));
+        assert_eq!(state.selected(), 2);
+
+        render(TabbedView::new(titles()), 20, &mut state);
+        assert!(state.handle_mouse_event(&click(0, 0)));
+        assert_eq!(state.selected(), 1);
+
+        let scroll = MouseEvent {
+            kind: MouseEventKind::ScrollDown,
+            ..click(5, 0)
+        };
+        assert!(state.handle_mouse_event(&scroll));
+        assert_eq!(state.selected(), 2);
+        let scroll = MouseEvent {
+            kind: MouseEventKind::ScrollDown,
+            ..click(5, 1)
+        };
+        assert!(!state.handle_mouse_event(&scroll));
+    }
+
+    #[test]
+    fn test_tabbed_view_selected_clamped() {
+        let mut state = TabbedViewState::new(10);
+        render(TabbedView::new(titles()), 40, &mut state);
+        assert_eq!(state.selected(), 3);
+
+        let mut state = TabbedViewState::new(1);
+        render(TabbedView::new(Vec::<&str>::new()), 40, &mut state);
+        assert_eq!(state.selected(), 0);
+        state.select_next();
+        assert_eq!(state.selected(), 0);
+    }
+}
@@ -0,0 +1,576 @@
+use std::{borrow::Cow, fmt, ops::Range};
+
+use ratatui_core::{
+    style::Style,
+    text::{Line, Span},
+};
+
+use crate::spans::{slice_spans, spans_width};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StyledText<'a> {
+    spans: Vec<Span<'a>>,
+}
+
+impl<'a> StyledText<'a> {
+    pub fn new(spans: Vec<Span<'a>>) -> Self {
+        Self { spans }
+    }
+
+    pub fn spans(&self) -> &[Span<'a>] {
+        &self.spans
+    }
+
+    pub fn into_spans(self) -> Vec<Span<'a>> {
+        self.spans
+    }
+
+    pub fn into_line(self) -> Line<'a> {
+        Line::from(self.spans)
+    }
+
+    pub fn len(&self) -> usize {
+        self.spans.iter().map(|s| s.content.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.iter().all(|s| s.content.is_empty())
+    }
+
+    pub fn width(&self) -> usize {
+        spans_width(&self.spans)
+    }
+
+    pub fn char_to_byte(&self, index: usize) -> usize {
+        self.chars()
+            .nth(index)
+            .map(|(start, _, _)| start)
+            .unwrap_or(self.len())
+    }
+
+    pub fn column_to_byte(&self, column: usize) -> usize {
+        self.chars()
+            .find(|(_, col, _)| *col >= column)
+            .map(|(start, _, _)| start)
+            .unwrap_or(self.len())
+    }
+
+    pub fn is_char_boundary(&self, pos: usize) -> bool {
+        let mut offset = 0;
+        for span in &self.spans {
+            let len = span.content.len();
+            if pos < offset + len {
+                return span.content.is_char_boundary(pos - offset);
+            }
+            offset += len;
+        }
+        true
+    }
+
+    pub fn slice(&self, range: Range<usize>) -> Option<StyledText<'a>> {
+        self.is_char_range(&range)
+            .then(|| self.slice_unchecked(range))
+    }
+
+    pub fn slice_chars(&self, range: Range<usize>) -> StyledText<'a> {
+        self.slice_unchecked(self.char_range(range))
+    }
+
+    pub fn slice_columns(&self, range: Range<usize>) -> StyledText<'a> {
+        self.slice_unchecked(self.column_range(range))
+    }
+
+    pub fn patch_style(&mut self, range: Range<usize>, style: Style) -> bool {
+        if !self.is_char_range(&range) {
+            return false;
+        }
+        self.patch_style_unchecked(range, style);
+        true
+    }
+
+    pub fn patch_style_chars(&mut self, range: Range<usize>, style: Style) {
+        self.patch_style_unchecked(self.char_range(range), style);
+    }
+
+    pub fn patch_style_columns(&mut self, range: Range<usize>, style: Style) {
+        self.patch_style_unchecked(self.column_range(range), style);
+    }
+
+    pub fn insert(&mut self, pos: usize, s: impl Into<Cow<'a, str>>) -> bool {
+        let style = self.style_before(pos);
+        self.insert_span(pos, Span::styled(s.into(), style))
+    }
+
+    pub fn insert_span(&mut self, pos: usize, span: Span<'a>) -> bool {
+        if !self.is_char_boundary(pos) {
+            return false;
+        }
+        let i = self.split_at(pos);
+        self.spans.insert(i, span);
+        true
+    }
+
+    pub fn replace(&mut self, range: Range<usize>, s: impl Into<Cow<'a, str>>) -> bool {
+        let style = if range.start < range.end {
+            self.style_after(range.start)
+        } else {
+            self.style_before(range.start)
+        };
+        self.replace_span(range, Span::styled(s.into(), style))
+    }
+
+    pub fn replace_span(&mut self, range: Range<usize>, span: Span<'a>) -> bool {
+        if !self.is_char_range(&range) {
+            return false;
+        }
+        let start = self.split_at(range.start);
+        let end = self.split_at(range.end.max(range.start));
+        self.spans.splice(start..end, [span]);
+        true
+    }
+
+    pub(crate) fn truncate(&mut self, len: usize) {
+        let mut offset = 0;
+        for (i, span) in self.spans.iter_mut().enumerate() {
+            if offset + span.content.len() >= len {
+                let at = len - offset;
+                if at == 0 {
+                    self.spans.truncate(i);
+                    return;
+                }
+                match &mut span.content {
+                    Cow::Borrowed(c) => *c = &c[..at],
+                    Cow::Owned(c) => c.truncate(at),
+                }
+                self.spans.truncate(i + 1);
+                return;
+            }
+            offset += span.content.len();
+        }
+    }
+
+    pub(crate) fn patch_sorted_styles<I>(self, ranges: I) -> StyledText<'a>
+    where
+        I: IntoIterator<Item = (Range<usize>, Style)>,
+    {
+        let mut ranges = ranges
+            .into_iter()
+            .filter(|(r, _)| r.start < r.end)
+            .peekable();
+        let mut ret = Vec::with_capacity(self.spans.len());
+        let mut offset = 0;
+        for span in self.spans {
+            let len = span.content.len();
+            let end = offset + len;
+            let mut content = span.content;
+            let mut pos = offset;
+            while pos < end {
+                while ranges.peek().is_some_and(|(r, _)| r.end <= pos) {
+                    ranges.next();
+                }
+                let (next, style) = match ranges.peek() {
+                    Some((r, style)) if r.start <= pos => (r.end, span.style.patch(*style)),
+                    Some((r, _)) => (r.start, span.style),
+                    None => (end, span.style),
+                };
+                let next = next.min(end);
+                let (s, e) = (pos - offset, next - offset);
+                let piece = match &content {
+                    _ if s == 0 && e == len => std::mem::take(&mut content),
+                    Cow::Borrowed(c) => Cow::Borrowed(&c[s..e]),
+                    Cow::Owned(c) => Cow::Owned(c[s..e].to_string()),
+                };
+                ret.push(Span::styled(piece, style));
+                pos = next;
+            }
+            offset = end;
+        }
+        Self::new(ret)
+    }
+
+    fn slice_unchecked(&self, range: Range<usize>) -> StyledText<'a> {
+        Self::new(slice_spans(&self.spans, range.start, range.end))
+    }
+
+    fn patch_style_unchecked(&mut self, range: Range<usize>, style: Style) {
+        if range.start >= range.end {
+            return;
+        }
+        let start = self.split_at(range.start);
+        let end = self.split_at(range.end);
+        for span in &mut self.spans[start..end] {
+            span.style = span.style.patch(style);
+        }
+    }
+
+    fn is_char_range(&self, range: &Range<usize>) -> bool {
+        self.is_char_boundary(range.start) && self.is_char_boundary(range.end)
+    }
+
+    fn chars(&self) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        let mut column = 0;
+        self.spans
+            .iter()
+            .scan(0, |offset, span| {
+                let start = *offset;
+                *offset += span.content.len();
+                Some((start, span))
+            })
+            .flat_map(|(start, span)| {
+                span.content
+                    .char_indices()
+                    .map(move |(i, c)| (start + i, c))
+            })
+            .map(move |(pos, c)| {
+                let start = column;
+                column += console::measure_text_width(c.encode_utf8(&mut [0; 4]));
+                (pos, start, column)
+            })
+    }
+
+    fn char_range(&self, range: Range<usize>) -> Range<usize> {
+        self.char_to_byte(range.start)..self.char_to_byte(range.end)
+    }
+
+    fn column_range(&self, range: Range<usize>) -> Range<usize> {
+        let start = self.column_to_byte(range.start);
+        let end = self
+            .chars()
+            .take_while(|(_, _, end)| *end <= range.end)
+            .last()
+            .map(|(start, _, _)| start)
+            .map(|last| last + self.char_len_at(last))
+            .unwrap_or(0);
+        start..end.max(start)
+    }
+
+    fn char_len_at(&self, pos: usize) -> usize {
+        let mut offset = 0;
+        for span in &self.spans {
+            let len = span.content.len();
+            if pos < offset + len {
+                return span.content[pos - offset..]
+                    .chars()
+                    .next()
+                    .map_or(0, char::len_utf8);
+            }
+            offset += len;
+        }
+        0
+    }
+
+    fn style_before(&self, pos: usize) -> Style {
+        let mut offset = 0;
+        let mut style = self.spans.first().map(|s| s.style).unwrap_or_default();
+        for span in &self.spans {
+            if offset >= pos {
+                break;
+            }
+            style = span.style;
+            offset += span.content.len();
+        }
+        style
+    }
+
+    fn style_after(&self, pos: usize) -> Style {
+        let mut offset = 0;
+        for span in &self.spans {
+            let len = span.content.len();
+            if pos < offset + len {
+                return span.style;
+            }
+            offset += len;
+        }
+        self.style_before(pos)
+    }
+
+    fn split_at(&mut self, pos: usize) -> usize {
+        let mut offset = 0;
+        for i in 0..self.spans.len() {
+            let len = self.spans[i].content.len();
+            if pos == offset {
+                return i;
+            }
+            if pos < offset + len {
+                let at = pos - offset;
+                let span = &mut self.spans[i];
+                let (head, tail) = match &span.content {
+                    Cow::Borrowed(c) => (Cow::Borrowed(&c[..at]), Cow::Borrowed(&c[at..])),
+                    Cow::Owned(c) => (
+                        Cow::Owned(c[..at].to_string()),
+                        Cow::Owned(c[at..].to_string()),
+                    ),
+                };
+                let style = span.style;
+                span.content = head;
+                self.spans.insert(i + 1, Span::styled(tail, style));
+                return i + 1;
+            }
+            offset += len;
+        }
+        self.spans.len()
+    }
+}
+
+impl<'a> From<Vec<Span<'a>>> for StyledText<'a> {
+    fn from(spans: Vec<Span<'a>>) -> Self {
+        Self::new(spans)
+    }
+}
+
+impl<'a> From<Line<'a>> for StyledText<'a> {
+    fn from(line: Line<'a>) -> Self {
+        let style = line.style;
+        Self::new(
+            line.spans
+                .into_iter()
+                .map(|s| {
+                    let span_style = style.patch(s.style);
+                    s.style(span_style)
+                })
+                .collect(),
+        )
+    }
+}
+
+impl<'a> From<Span<'a>> for StyledText<'a> {
+    fn from(span: Span<'a>) -> Self {
+        Self::new(vec![span])
+    }
+}
+
+impl<'a> From<&'a str> for StyledText<'a> {
+    fn from(s: &'a str) -> Self {
+        Self::new(vec![Span::raw(s)])
+    }
+}
+
+impl<'a> From<StyledText<'a>> for Line<'a> {
+    fn from(text: StyledText<'a>) -> Self {
+        text.into_line()
+    }
+}
+
+impl fmt::Display for StyledText<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for span in &self.spans {
+            f.write_str(&span.content)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui_core::style::{Color, Stylize};
+    use rstest::*;
+
+    use super::*;
+
+    fn text() -> StyledText<'static> {
+        StyledText::new(vec![
+            Span::raw("foo ").red(),
+            Span::raw("日本語"),
+            Span::raw(" bar").bold(),
+        ])
+    }
+
+    #[test]
+    fn test_styled_text_measure() {
+        let text = text();
+        assert_eq!(text.len(), 17);
+        assert_eq!(text.width(), 14);
+        assert!(!text.is_empty());
+        assert!(StyledText::default().is_empty());
+        assert_eq!(text.to_string(), "foo 日本語 bar");
+    }
+
+    #[rstest]
+    #[case(0, 0)]
+    #[case(4, 4)]
+    #[case(5, 7)]
+    #[case(7, 13)]
+    #[case(11, 17)]
+    #[case(20, 17)]
+    fn test_char_to_byte(#[case] index: usize, #[case] expected: usize) {
+        assert_eq!(text().char_to_byte(index), expected);
+    }
+
+    #[rstest]
+    #[case(0..3, vec![Span::raw("foo").red()])]
+    #[case(2..7, vec![Span::raw("o ").red(), Span::raw("日")])]
+    #[case(13..17, vec![Span::raw(" bar").bold()])]
+    #[case(4..4, vec![])]
+    fn test_slice(#[case] range: Range<usize>, #[case] expected: Vec<Span>) {
+        assert_eq!(text().slice(range).unwrap().into_spans(), expected);
+    }
+
+    #[rstest]
+    #[case(0, true)]
+    #[case(4, true)]
+    #[case(5, false)]
+    #[case(6, false)]
+    #[case(7, true)]
+    #[case(17, true)]
+    #[case(20, true)]
+    fn test_is_char_boundary(#[case] pos: usize, #[case] expected: bool) {
+        assert_eq!(text().is_char_boundary(pos), expected);
+    }
+
+    #[test]
+    fn test_not_char_boundary() {
+        let mut text = text();
+        assert_eq!(text.slice(0..5), None);
+        assert_eq!(text.slice(5..7), None);
+        assert!(!text.patch_style(2..6, Style::default().underlined()));
+        assert!(!text.insert(5, "x"));
+        assert!(!text.insert_span(6, Span::raw("x")));
+        assert!(!text.replace(3..5, "x"));
+        assert!(!text.replace_span(5..13, Span::raw("x")));
+        assert_eq!(text, self::text());
+    }
+
+    #[rstest]
+    #[case(3..6, vec![Span::raw(" ").red(), Span::raw("日本")])]
+    #[case(0..1, vec![Span::raw("f").red()])]
+    fn test_slice_chars(#[case] range: Range<usize>, #[case] expected: Vec<Span>) {
+        assert_eq!(text().slice_chars(range).into_spans(), expected);
+    }
+
+    #[rstest]
+    #[case(0..4, vec![Span::raw("foo ").red()])]
+    #[case(4..8, vec![Span::raw("日本")])]
+    #[case(5..9, vec![Span::raw("本")])]
+    #[case(5..10, vec![Span::raw("本語")])]
+    #[case(9..12, vec![Span::raw(" b").bold()])]
+    #[case(12..100, vec![Span::raw("ar").bold()])]
+    #[case(5..6, vec![])]
+    fn test_slice_columns(#[case] range: Range<usize>, #[case] expected: Vec<Span>) {
+        assert_eq!(text().slice_columns(range).into_spans(), expected);
+    }
+
+    #[test]
+    fn test_patch_style() {
+        let mut text = text();
+        text.patch_style(2..10, Style::default().bg(Color::Blue));
+        assert_eq!(
+            text.into_spans(),
+            vec![
+                Span::raw("fo").red(),
+                Span::raw("o ").red().on_blue(),
+                Span::raw("日本").on_blue(),
+                Span::raw("語"),
+                Span::raw(" bar").bold(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_patch_style_chars_and_columns() {
+        let mut text = text();
+        text.patch_style_chars(5..6, Style::default().underlined());
+        text.patch_style_columns(11..13, Style::default().italic());
+        assert_eq!(
+            text.into_spans(),
+            vec![
+                Span::raw("foo ").red(),
+                Span::raw("日"),
+                Span::raw("本").underlined(),
+                Span::raw("語"),
+                Span::raw(" ").bold(),
+                Span::raw("ba").bold().italic(),
+                Span::raw("r").bold(),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case(0, vec![])]
+    #[case(2, vec![Span::raw("fo").red()])]
+    #[case(4, vec![Span::raw("foo ").red()])]
+    #[case(7, vec![Span::raw("foo ").red(), Span::raw("日")])]
+    #[case(17, vec![Span::raw("foo ").red(), Span::raw("日本語"), Span::raw(" bar").bold()])]
+    #[case(20, vec![Span::raw("foo ").red(), Span::raw("日本語"), Span::raw(" bar").bold()])]
+    fn test_truncate(#[case] len: usize, #[case] expected: Vec<Span>) {
+        let mut text = text();
+        text.truncate(len);
+        assert_eq!(text.into_spans(), expected);
+    }
+
+    #[test]
+    fn test_patch_sorted_styles() {
+        let underlined = Style::default().underlined();
+        let actual = text().patch_sorted_styles([
+            (0..2, underlined),
+            (3..3, underlined),
+            (3..10, Style::default().on_blue()),
+            (16..20, underlined),
+        ]);
+        assert_eq!(
+            actual.into_spans(),
+            vec![
+                Span::raw("fo").red().underlined(),
+                Span::raw("o").red(),
+                Span::raw(" ").red().on_blue(),
+                Span::raw("日本").on_blue(),
+                Span::raw("語"),
+                Span::raw(" ba").bold(),
+                Span::raw("r").bold().underlined(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut text = text();
+        text.insert(4, "new ");
+        text.insert(0, ">");
+        text.insert_span(text.len(), Span::raw("!").green());
+        assert_eq!(
+            text.into_spans(),
+            vec![
+                Span::raw(">").red(),
+                Span::raw("foo ").red(),
+                Span::raw("new ").red(),
+                Span::raw("日本語"),
+                Span::raw(" bar").bold(),
+                Span::raw("!").green(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replace() {
+        let mut text = text();
+        text.replace(7..13, String::from("ー"));
+        text.replace(11..14, "baz");
+        assert_eq!(text.to_string(), "foo 日ー baz");
+        assert_eq!(
+            text.into_spans(),
+            vec![
+                Span::raw("foo ").red(),
+                Span::raw("日"),
+                Span::raw("ー"),
+                Span::raw(" ").bold(),
+                Span::raw("baz").bold(),
+            ]
+        );
+
+        let mut text = StyledText::from("abc");
+        text.replace_span(1..2, Span::raw("X").red());
+        assert_eq!(
+            text.into_spans(),
+            vec![Span::raw("a"), Span::raw("X").red(), Span::raw("c")]
+        );
+    }
+
+    #[test]
+    fn test_from_line() {
+        let line = Line::from(vec![Span::raw("a"), Span::raw("b").bold()]).red();
+        let text = StyledText::from(line);
+        assert_eq!(
+            text.into_spans(),
+            vec![Span::raw("a").red(), Span::raw("b").red().bold()]
+        );
+    }
+}
@@ -0,0 +1,430 @@
+use std::ops::Range;
+
+use ratatui_core::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+use crate::spans::{slice_spans, spans_width, take_head};
+
+pub fn wrap_spans(spans: Vec<Span<'_>>, width: usize) -> WrapSpans<'_> {
+    WrapSpans {
+        spans,
+        width,
+        hanging_indent: 0,
+        max_lines: None,
+        ellipsis: "",
+        ellipsis_style: Style::default(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrappedLine<'a> {
+    pub line: Line<'a>,
+    pub range: Range<usize>,
+}
+
+pub struct WrapSpans<'a> {
+    spans: Vec<Span<'a>>,
+    width: usize,
+    hanging_indent: usize,
+    max_lines: Option<usize>,
+    ellipsis: &'a str,
+    ellipsis_style: Style,
+}
+
+impl<'a> WrapSpans<'a> {
+    pub fn hanging_indent(mut self, indent: usize) -> Self {
+        self.hanging_indent = indent;
+        self
+    }
+
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = Some(max_lines);
+        self
+    }
+
+    pub fn ellipsis(mut self, ellipsis: &'a str) -> Self {
+        self.ellipsis = ellipsis;
+        self
+    }
+
+    pub fn ellipsis_style(mut self, style: Style) -> Self {
+        self.ellipsis_style = style;
+        self
+    }
+
+    pub fn ellipsis_fg(mut self, color: Color) -> Self {
+        self.ellipsis_style = self.ellipsis_style.fg(color);
+        self
+    }
+
+    pub fn ellipsis_bg(mut self, color: Color) -> Self {
+        self.ellipsis_style = self.ellipsis_style.bg(color);
+        self
+    }
+
+    pub fn ellipsis_modifier(mut self, modifier: Modifier) -> Self {
+        self.ellipsis_style = self.ellipsis_style.add_modifier(modifier);
+        self
+    }
+
+    pub fn into_lines(self) -> Vec<Line<'a>> {
+        self.into_wrapped_lines()
+            .into_iter()
+            .map(|l| l.line)
+            .collect()
+    }
+
+    pub fn into_wrapped_lines(self) -> Vec<WrappedLine<'a>> {
+        if self.width == 0 || self.max_lines == Some(0) {
+            return Vec::new();
+        }
+
+        let text: String = self.spans.iter().map(|s| s.content.as_ref()).collect();
+        let mut ranges = self.wrap(&text);
+
+        let truncated = match self.max_lines {
+            Some(max) if ranges.len() > max => {
+                ranges.truncate(max);
+                true
+            }
+            _ => false,
+        };
+
+        let n = ranges.len();
+        ranges
+            .into_iter()
+            .enumerate()
+            .map(|(i, range)| {
+                let mut spans = slice_spans(&self.spans, range.start, range.end);
+                let mut range = range;
+                if truncated && i == n - 1 {
+                    let width = self.line_width(i);
+                    let ellipsis = console::truncate_str(self.ellipsis, width, "");
+                    let ellipsis_w = console::measure_text_width(&ellipsis);
+                    spans = ellipsis_head(spans, width, ellipsis_w);
+                    range.end = range.start + spans.iter().map(|s| s.content.len()).sum::<usize>();
+                    if !ellipsis.is_empty() {
+                        spans.push(Span::styled(ellipsis, self.ellipsis_style));
+                    }
+                }
+                if i > 0 && self.indent() > 0 {
+                    spans.insert(0, Span::raw(" ".repeat(self.indent())));
+                }
+                WrappedLine {
+                    line: Line::from(spans),
+                    range,
+                }
+            })
+            .collect()
+    }
+
+    fn indent(&self) -> usize {
+        self.hanging_indent.min(self.width.saturating_sub(1))
+    }
+
+    fn line_width(&self, i: usize) -> usize {
+        if i == 0 {
+            self.width
+        } else {
+            self.width - self.indent()
+        }
+    }
+
+    fn wrap(&self, text: &str) -> Vec<Range<usize>> {
+        let mut lines = Vec::new();
+        let mut offset = 0;
+        for paragraph in text.split('\n') {
+            let mut state = LineState::new(offset);
+            for token in tokens(paragraph, offset) {
+                let limit = self.line_width(lines.len());
+                if token.whitespace {
+                    if state.wrapped && state.start.is_none() {
+                        continue;
+                    }
+                    if state.width + token.width <= limit {
+                        state.push_whitespace(&token);
+                    } else if state.start.is_some() {
+                        state.break_line(&mut lines);
+                    }
+                    continue;
+                }
+
+                if state.start.is_some() && state.width + token.width > limit {
+                    state.break_line(&mut lines);
+                }
+                let limit = self.line_width(lines.len());
+                if token.width <= limit {
+                    state.push_word(token.range.clone(), token.width);
+                    continue;
+                }
+
+                for (i, c) in text[token.range.clone()].char_indices() {
+                    let start = token.range.start + i;
+                    let end = start + c.len_utf8();
+                    let w = console::measure_text_width(&text[start..end]);
+                    if state.start.is_some() && state.width + w > self.line_width(lines.len()) {
+                        state.break_line(&mut lines);
+                    }
+                    if w > self.line_width(lines.len()) {
+                        continue;
+                    }
+                    state.push_word(start..end, w);
+                }
+            }
+            let line = state.start.unwrap_or(state.content_end)..state.content_end;
+            if !(state.wrapped && line.is_empty()) {
+                lines.push(line);
+            }
+            offset += paragraph.len() + 1;
+        }
+        lines
+    }
+}
+
+fn ellipsis_head(spans: Vec<Span<'_>>, width: usize, ellipsis_w: usize) -> Vec<Span<'_>> {
+    if spans_width(&spans) + ellipsis_w <= width {
+        return spans;
+    }
+    let head = take_head(spans, width.saturating_sub(ellipsis_w));
+    let text: String = head.iter().map(|s| s.content.as_ref()).collect();
+    slice_spans(&head, 0, text.trim_end().len())
+}
+
+struct LineState {
+    start: Option<usize>,
+    content_end: usize,
+    width: usize,
+    wrapped: bool,
+}
+
+impl LineState {
+    fn new(offset: usize) -> Self {
+        Self {
+            start: None,
+            content_end: offset,
+            width: 0,
+            wrapped: false,
+        }
+    }
+
+    fn push_whitespace(&mut self, token: &Token) {
+        if self.start.is_none() {
+            self.start = Some(token.range.start);
+            self.content_end = token.range.start;
+        }
+        self.width += token.width;
+    }
+
+    fn push_word(&mut self, range: Range<usize>, width: usize) {
+        if self.start.is_none() {
+            self.start = Some(range.start);
+        }
+        self.content_end = range.end;
+        self.width += width;
+    }
+
+    fn break_line(&mut self, lines: &mut Vec<Range<usize>>) {
+        let start = self.start.unwrap_or(self.content_end);
+        lines.push(start..self.content_end.max(start));
+        self.start = None;
+        self.width = 0;
+        self.wrapped = true;
+    }
+}
+
+struct Token {
+    range: Range<usize>,
+    width: usize,
+    whitespace: bool,
+}
+
+fn tokens(s: &str, offset: usize) -> Vec<Token> {
+    let mut tokens: Vec<Token> = Vec::new();
+    for (i, c) in s.char_indices() {
+        let whitespace = c.is_whitespace();
+        let start = offset + i;
+        let end = start + c.len_utf8();
+        let w = console::measure_text_width(&s[i..i + c.len_utf8()]);
+        match tokens.last_mut() {
+            Some(t) if t.whitespace == whitespace => {
+                t.range.end = end;
+                t.width += w;
+            }
+            _ => tokens.push(Token {
+                range: start..end,
+                width: w,
+                whitespace,
+            }),
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui_core::style::Stylize;
+    use rstest::*;
+
+    use super::*;
+
+    fn lines_to_strings(lines: &[Line]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect()
+    }
+
+    fn assert_lines_width(lines: &[Line], width: usize) {
+        for line in lines {
+            assert!(line.width() <= width, "{line:?} is wider than {width}");
+        }
+    }
+
+    #[rstest]
+    #[case("hello world", 20, vec!["hello world"])]
+    #[case("hello world", 11, vec!["hello world"])]
+    #[case("hello world", 10, vec!["hello", "world"])]
+    #[case("hello world", 5, vec!["hello", "world"])]
+    #[case("a b c d e f", 3, vec!["a b", "c d", "e f"])]
+    #[case("a  b   c", 4, vec!["a  b", "c"])]
+    #[case("abcdefghij", 4, vec!["abcd", "efgh", "ij"])]
+    #[case("ab cdefghij", 4, vec!["ab", "cdef", "ghij"])]
+    #[case("  indented text", 10, vec!["  indented", "text"])]
+    #[case("one\ntwo three", 5, vec!["one", "two", "three"])]
+    #[case("one\n\ntwo", 5, vec!["one", "", "two"])]
+    #[case("", 5, vec![""])]
+    #[case("日本語のテキスト", 5, vec!["日本", "語の", "テキ", "スト"])]
+    #[case("ab 日本語", 5, vec!["ab", "日本", "語"])]
+    #[case("      abc", 3, vec!["abc"])]
+    #[case("ab\n      cd", 3, vec!["ab", "cd"])]
+    #[case("日本", 1, vec![""])]
+    #[case("a日b", 1, vec!["a", "b"])]
+    fn test_wrap_spans(#[case] s: &str, #[case] width: usize, #[case] expected: Vec<&str>) {
+        let actual = wrap_spans(vec![Span::raw(s)], width).into_lines();
+        assert_eq!(lines_to_strings(&actual), expected);
+        assert_lines_width(&actual, width);
+    }
+
+    #[test]
+    fn test_wrap_spans_styles() {
+        let spans = vec![
+            Span::raw("foo ").red(),
+            Span::raw("bar baz").bold(),
+            Span::raw(" qux"),
+        ];
+        let actual = wrap_spans(spans, 7).into_lines();
+        let expected = vec![
+            Line::from(vec![Span::raw("foo ").red(), Span::raw("bar").bold()]),
+            Line::from(vec![Span::raw("baz").bold(), Span::raw(" qux")]),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_wrap_spans_hanging_indent() {
+        let spans = vec![Span::raw("- first item that wraps")];
+        let actual = wrap_spans(spans, 10).hanging_indent(2).into_lines();
+        assert_eq!(
+            lines_to_strings(&actual),
+            vec!["- first", "  item", "  that", "  wraps"]
+        );
+        assert_lines_width(&actual, 10);
+    }
+
+    #[rstest]
+    #[case(3, vec!["aaa", "  b", "  b", "  b"])]
+    #[case(5, vec!["aaa", "  b", "  b", "  b"])]
+    fn test_wrap_spans_hanging_indent_clamped(#[case] indent: usize, #[case] expected: Vec<&str>) {
+        let spans = vec![Span::raw("aaa bbb")];
+        let actual = wrap_spans(spans, 3).hanging_indent(indent).into_lines();
+        assert_eq!(lines_to_strings(&actual), expected);
+        assert_lines_width(&actual, 3);
+    }
+
+    #[rstest]
+    #[case(2, "…", vec!["aaa bbb", "ccc ddd…"])]
+    #[case(2, "...", vec!["aaa bbb", "ccc d..."])]
+    #[case(1, "…", vec!["aaa bbb…"])]
+    #[case(1, "....", vec!["aaa...."])]
+    #[case(3, "…", vec!["aaa bbb", "ccc ddd", "eee"])]
+    #[case(0, "…", vec![])]
+    fn test_wrap_spans_max_lines(
+        #[case] max_lines: usize,
+        #[case] ellipsis: &str,
+        #[case] expected: Vec<&str>,
+    ) {
+        let spans = vec![Span::raw("aaa bbb ccc ddd eee")];
+        let actual = wrap_spans(spans, 8)
+            .max_lines(max_lines)
+            .ellipsis(ellipsis)
+            .into_lines();
+        assert_eq!(lines_to_strings(&actual), expected);
+        assert_lines_width(&actual, 8);
+    }
+
+    #[rstest]
+    #[case(1, "...", vec![".."])]
+    #[case(2, "...", vec!["aa", ".."])]
+    #[case(2, "…", vec!["aa", "b…"])]
+    #[case(1, "日本", vec!["日"])]
+    fn test_wrap_spans_ellipsis_wider_than_line(
+        #[case] max_lines: usize,
+        #[case] ellipsis: &str,
+        #[case] expected: Vec<&str>,
+    ) {
+        let spans = vec![Span::raw("aa bb cc")];
+        let actual = wrap_spans(spans, 2)
+            .max_lines(max_lines)
+            .ellipsis(ellipsis)
+            .into_lines();
+        assert_eq!(lines_to_strings(&actual), expected);
+        assert_lines_width(&actual, 2);
+    }
+
+    #[test]
+    fn test_wrap_spans_ellipsis_style() {
+        let spans = vec![Span::raw("aaa bbb")];
+        let actual = wrap_spans(spans, 4)
+            .max_lines(1)
+            .ellipsis("…")
+            .ellipsis_fg(Color::DarkGray)
+            .into_lines();
+        let expected = vec![Line::from(vec![
+            Span::raw("aaa"),
+            Span::raw("…").fg(Color::DarkGray),
+        ])];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_wrap_spans_ranges() {
+        let spans = vec![Span::raw("hello "), Span::raw("wide 世界\nnext")];
+        let actual: Vec<Range<usize>> = wrap_spans(spans, 6)
+            .into_wrapped_lines()
+            .into_iter()
+            .map(|l| l.range)
+            .collect();
+        assert_eq!(actual, vec![0..5, 6..10, 11..17, 18..22]);
+    }
+
+    #[test]
+    fn test_wrap_spans_ranges_max_lines() {
+        let spans = vec![Span::raw("abc def ghi")];
+        let actual = wrap_spans(spans, 4)
+            .max_lines(2)
+            .ellipsis("…")
+            .into_wrapped_lines();
+        assert_eq!(actual[0].range, 0..3);
+        assert_eq!(actual[1].range, 4..7);
+        assert_eq!(lines_to_strings(&[actual[1].line.clone()]), vec!["def…"]);
+
+        let actual = wrap_spans(vec![Span::raw("abcd efgh")], 4)
+            .max_lines(1)
+            .ellipsis("…")
+            .into_wrapped_lines();
+        assert_eq!(actual[0].range, 0..3);
+        assert_eq!(lines_to_strings(&[actual[0].line.clone()]), vec!["abc…"]);
+    }
+}